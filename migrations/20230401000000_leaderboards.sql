@@ -0,0 +1,23 @@
+-- Existing deployments created these tables by hand, so everything here is idempotent
+
+CREATE TABLE IF NOT EXISTS submissions (
+    game            TEXT    NOT NULL,
+    unix_time_stamp BIGINT  NOT NULL,
+    valid           BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (game, unix_time_stamp)
+);
+
+CREATE INDEX IF NOT EXISTS submissions_unix_time_stamp_idx ON submissions (unix_time_stamp);
+CREATE INDEX IF NOT EXISTS submissions_valid_game_idx ON submissions (game, unix_time_stamp) WHERE valid;
+
+CREATE TABLE IF NOT EXISTS leaderboards (
+    player          TEXT    NOT NULL,
+    position        INTEGER NOT NULL,
+    score           INTEGER NOT NULL,
+    game            TEXT    NOT NULL,
+    unix_time_stamp BIGINT  NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS leaderboards_game_unix_time_stamp_idx ON leaderboards (game, unix_time_stamp, position);
+CREATE INDEX IF NOT EXISTS leaderboards_player_idx ON leaderboards (player);
+CREATE INDEX IF NOT EXISTS leaderboards_lower_player_idx ON leaderboards (LOWER(player) text_pattern_ops);
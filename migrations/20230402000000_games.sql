@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS games (
+    code       TEXT    PRIMARY KEY,
+    name       TEXT    NOT NULL,
+    score_unit TEXT    NOT NULL,
+    descending BOOLEAN NOT NULL DEFAULT TRUE,
+    active     BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+-- The games that used to be hardcoded in the commands
+INSERT INTO
+    games (code, name, score_unit, descending, active)
+VALUES
+    ('tew', 'Team EggWars', 'wins', TRUE, TRUE),
+    ('tew2', 'Team EggWars Season 2', 'wins', TRUE, TRUE),
+    ('s_sw', 'Solo SkyWars', 'wins', TRUE, TRUE),
+    ('s_li', 'Lucky Islands', 'wins', TRUE, TRUE),
+    ('ffa', 'Free For All', 'kills', TRUE, TRUE),
+    ('parkour', 'Parkour', 'medals', TRUE, TRUE),
+    ('ss', 'Snowman Survival', 'medals', TRUE, TRUE)
+ON CONFLICT (code) DO NOTHING;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS paginator_sessions (
+    id         BIGINT      PRIMARY KEY,
+    pages      TEXT[]      NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS paginator_sessions_created_at_idx ON paginator_sessions (created_at);
@@ -0,0 +1,2 @@
+-- Who uploaded it through /leaderboard submit, empty for older submissions
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS submitter BIGINT;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    id         BIGSERIAL   PRIMARY KEY,
+    actor      BIGINT      NOT NULL,
+    action     TEXT        NOT NULL,
+    target     TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_created_at_idx ON audit_log (created_at);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS follows (
+    discord_user_id BIGINT NOT NULL,
+    player          TEXT   NOT NULL,
+    -- Where to post the notifications, empty means a DM
+    channel_id      BIGINT,
+    PRIMARY KEY (discord_user_id, player)
+);
+
+CREATE INDEX IF NOT EXISTS follows_player_idx ON follows (player);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS announcement_channels (
+    guild_id   BIGINT NOT NULL,
+    game       TEXT   NOT NULL,
+    channel_id BIGINT NOT NULL,
+    PRIMARY KEY (guild_id, game)
+);
+
+CREATE INDEX IF NOT EXISTS announcement_channels_game_idx ON announcement_channels (game);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS linked_accounts (
+    discord_user_id BIGINT PRIMARY KEY,
+    player          TEXT   NOT NULL
+);
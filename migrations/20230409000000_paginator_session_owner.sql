@@ -0,0 +1,4 @@
+-- Pages are stored as the JSON of their embeds from now on, older sessions can't be shown anymore
+DELETE FROM paginator_sessions;
+
+ALTER TABLE paginator_sessions ADD COLUMN IF NOT EXISTS owner BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE paginator_sessions ADD COLUMN IF NOT EXISTS find_player TEXT;
@@ -0,0 +1,13 @@
+-- Lets the bot refresh its cached leaderboards, the payload is the name of the game
+CREATE OR REPLACE FUNCTION notify_submissions_changed() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('submissions_changed', COALESCE(NEW.game, OLD.game));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS submissions_changed ON submissions;
+
+CREATE TRIGGER submissions_changed
+    AFTER INSERT OR DELETE OR UPDATE OF valid ON submissions
+    FOR EACH ROW EXECUTE FUNCTION notify_submissions_changed();
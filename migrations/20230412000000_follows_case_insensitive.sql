@@ -0,0 +1,17 @@
+-- Player names are matched regardless of case, so following `fesa` and `Fesa` is the same follow
+DELETE FROM
+    follows a
+USING
+    follows b
+WHERE
+    a.discord_user_id = b.discord_user_id
+AND
+    LOWER(a.player) = LOWER(b.player)
+AND
+    a.player > b.player;
+
+ALTER TABLE follows DROP CONSTRAINT IF EXISTS follows_pkey;
+DROP INDEX IF EXISTS follows_player_idx;
+
+CREATE UNIQUE INDEX IF NOT EXISTS follows_discord_user_id_lower_player_idx ON follows (discord_user_id, LOWER(player));
+CREATE INDEX IF NOT EXISTS follows_lower_player_idx ON follows (LOWER(player));
@@ -0,0 +1,6 @@
+-- Whether the watcher announced the submission yet, everything before this was already
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS announced BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE submissions SET announced = TRUE;
+
+CREATE INDEX IF NOT EXISTS submissions_unannounced_idx ON submissions (unix_time_stamp) WHERE valid AND NOT announced;
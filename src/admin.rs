@@ -0,0 +1,762 @@
+use chrono::Utc;
+use serenity::{
+    builder::{CreateApplicationCommand, CreateEmbed, CreateInteractionResponseData},
+    model::{
+        prelude::{
+            command::{Command, CommandOptionType},
+            component::ButtonStyle,
+            interaction::{
+                application_command::{
+                    ApplicationCommandInteraction, CommandDataOption, CommandDataOptionValue,
+                },
+                message_component::MessageComponentInteraction,
+                InteractionResponseType,
+            },
+            GuildId,
+        },
+        Permissions,
+    },
+    prelude::Context,
+    utils::Colour,
+};
+
+use crate::{
+    audit, commands,
+    common::{default_embed_from_content, find_option},
+    games::{Game, GameRegistry},
+    paginator::{description_pages, paginate},
+    queries::SubmissionSummary,
+    Bot,
+};
+
+pub async fn run<'a>(
+    ctx: &Context,
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+) -> CreateInteractionResponseData<'a> {
+    if !bot.config.owner_ids.contains(command.user.id.as_u64()) {
+        return reply(
+            command,
+            String::from("You're not allowed to do that."),
+            Colour::RED,
+        );
+    }
+
+    let options = &command.data.options;
+    let Some(sub_option) = options.first() else {
+        return reply(
+            command,
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+
+    match sub_option.name.as_str() {
+        "sync" => sync_command(ctx, bot, command, &sub_option.options).await,
+        "invalidate" => confirm_command(bot, command, &sub_option.options, "invalidate").await,
+        "revalidate" => confirm_command(bot, command, &sub_option.options, "revalidate").await,
+        "submission" => submission_command(bot, command, &sub_option.options).await,
+        "submissions" => submissions_command(bot, command, &sub_option.options).await,
+        "audit" => audit_command(bot, command).await,
+        "delete" => confirm_command(bot, command, &sub_option.options, "delete").await,
+        "game" => match sub_option.options.first() {
+            Some(group_option) if group_option.name == "add" => {
+                add_game_command(ctx, bot, command, &group_option.options).await
+            }
+            Some(group_option) if group_option.name == "retire" => {
+                retire_game_command(ctx, bot, command, &group_option.options).await
+            }
+            _ => reply(
+                command,
+                String::from("Not a valid sub command. What happened here?"),
+                Colour::RED,
+            ),
+        },
+        _ => reply(
+            command,
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        ),
+    }
+}
+
+pub fn register<'a>(
+    command: &'a mut CreateApplicationCommand,
+    games: &GameRegistry,
+) -> &'a mut CreateApplicationCommand {
+    command
+        .name("lbadmin")
+        .description("Manage the leaderboard bot (owners only)")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("sync")
+                .description("Register the slash commands")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("scope")
+                        .description("Where to register the commands")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("This bot's home guild", "guild")
+                        .add_string_choice("Globally", "global")
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("invalidate")
+                .description("Hide a submission from all commands")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| submission_id_option(suboption))
+        })
+        .create_option(|option| {
+            option
+                .name("revalidate")
+                .description("Show an invalidated submission again")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| submission_id_option(suboption))
+        })
+        .create_option(|option| {
+            option
+                .name("submission")
+                .description("Show the details of a submission")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| submission_id_option(suboption))
+        })
+        .create_option(|option| {
+            option
+                .name("submissions")
+                .description("List the submissions, newest first")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("Only list submissions of this game")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+                    games.add_choices(suboption)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("audit")
+                .description("Browse the log of administrative actions, newest first")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("delete")
+                .description("Permanently delete a submission and its leaderboard")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| submission_id_option(suboption))
+        })
+        .create_option(|option| {
+            option
+                .name("game")
+                .description("Manage the games")
+                .kind(CommandOptionType::SubCommandGroup)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("add")
+                        .description("Add a game, or update and reactivate an existing one")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|o| {
+                            o.name("code")
+                                .description("Short code used in the commands, e.g. tew")
+                                .kind(CommandOptionType::String)
+                                .max_length(32)
+                                .required(true)
+                        })
+                        .create_sub_option(|o| {
+                            o.name("name")
+                                .description("Name as stored in the leaderboards")
+                                .kind(CommandOptionType::String)
+                                .max_length(100)
+                                .required(true)
+                        })
+                        .create_sub_option(|o| {
+                            o.name("unit")
+                                .description("What the score counts, e.g. wins")
+                                .kind(CommandOptionType::String)
+                                .max_length(32)
+                                .required(true)
+                        })
+                        .create_sub_option(|o| {
+                            o.name("direction")
+                                .description("Which scores rank higher")
+                                .kind(CommandOptionType::String)
+                                .add_string_choice("Higher is better", "desc")
+                                .add_string_choice("Lower is better", "asc")
+                                .required(true)
+                        })
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("retire")
+                        .description("Hide a game from the commands")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|o| {
+                            o.name("game")
+                                .description("The game")
+                                .kind(CommandOptionType::String)
+                                .required(true);
+                            games.add_choices(o)
+                        })
+                })
+        })
+}
+
+fn submission_id_option(
+    option: &mut serenity::builder::CreateApplicationCommandOption,
+) -> &mut serenity::builder::CreateApplicationCommandOption {
+    option
+        .name("id")
+        .description("The submission ID")
+        .kind(CommandOptionType::Integer)
+        .required(true)
+}
+
+/// Registers the admin command in the home guild so it can be used to sync everything else
+pub async fn register_in_home_guild(ctx: &Context, bot: &Bot) {
+    let games = bot.games();
+    if let Err(why) = GuildId(bot.config.home_guild_id)
+        .create_application_command(&ctx.http, |command| register(command, &games))
+        .await
+    {
+        println!("Cannot register the admin command: {}", why);
+    }
+}
+
+fn reply(
+    command: &ApplicationCommandInteraction,
+    content: String,
+    colour: Colour,
+) -> CreateInteractionResponseData<'static> {
+    default_embed_from_content(
+        &command.user.name,
+        &command.user.avatar_url().unwrap_or_default(),
+        content,
+        colour,
+    )
+}
+
+async fn sync_command<'a>(
+    ctx: &Context,
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let games = bot.games();
+    match find_option(option, "scope") {
+        Some(CommandDataOptionValue::String(scope)) if scope == "guild" => {
+            match GuildId(bot.config.home_guild_id)
+                .set_application_commands(&ctx.http, |commands| {
+                    commands
+                        .create_application_command(|command| commands::register(command, &games))
+                        .create_application_command(|command| register(command, &games))
+                })
+                .await
+            {
+                Ok(v) => {
+                    audit::record(ctx, bot, *command.user.id.as_u64(), "sync", "guild");
+                    reply(
+                        command,
+                        format!("Synced {} commands to the guild.", v.len()),
+                        Colour::from_rgb(106, 86, 246),
+                    )
+                }
+                Err(err) => reply(command, err.to_string(), Colour::RED),
+            }
+        }
+        Some(CommandDataOptionValue::String(scope)) if scope == "global" => {
+            match Command::create_global_application_command(&ctx.http, |command| {
+                commands::register(command, &games)
+            })
+            .await
+            {
+                Ok(_) => {
+                    audit::record(ctx, bot, *command.user.id.as_u64(), "sync", "global");
+                    reply(
+                        command,
+                        String::from("Synced commands globally."),
+                        Colour::from_rgb(106, 86, 246),
+                    )
+                }
+                Err(err) => reply(command, err.to_string(), Colour::RED),
+            }
+        }
+        _ => reply(
+            command,
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        ),
+    }
+}
+
+fn submission_id(option: &[CommandDataOption]) -> Option<i64> {
+    match find_option(option, "id") {
+        Some(CommandDataOptionValue::Integer(id)) => Some(*id),
+        _ => None,
+    }
+}
+
+fn submission_embed(submission: &SubmissionSummary, avatar_url: String) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Submission ID: {}", submission.unix_time_stamp))
+            .icon_url(avatar_url)
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(if submission.valid {
+        Colour::from_rgb(106, 86, 246)
+    } else {
+        Colour::RED
+    });
+    embed.field("Game", &submission.game, true);
+    embed.field("Players", submission.row_count, true);
+    embed.field("Valid", if submission.valid { "Yes" } else { "No" }, true);
+    embed.field(
+        "Submitter",
+        submission
+            .submitter
+            .map(|id| format!("<@{}>", id))
+            .unwrap_or_else(|| String::from("unknown")),
+        true,
+    );
+    embed.field(
+        "Submitted",
+        format!("<t:{}:f>", submission.unix_time_stamp),
+        true,
+    );
+    embed
+}
+
+async fn submission_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(id) = submission_id(option) else {
+        return reply(
+            command,
+            String::from("Integer was not an integer?"),
+            Colour::RED,
+        );
+    };
+
+    match bot.store.submission_summary(id).await {
+        Ok(Some(submission)) => {
+            let mut message = CreateInteractionResponseData::default();
+            message.add_embed(submission_embed(
+                &submission,
+                command.user.avatar_url().unwrap_or_default(),
+            ));
+            message
+        }
+        Ok(None) => reply(
+            command,
+            format!("There is no submission with ID: {}", id),
+            Colour::RED,
+        ),
+        Err(err) => reply(command, err.to_string(), Colour::RED),
+    }
+}
+
+/// Shows what `action` would affect and asks for confirmation, see [`handle_component`]
+async fn confirm_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+    action: &str,
+) -> CreateInteractionResponseData<'a> {
+    let Some(id) = submission_id(option) else {
+        return reply(
+            command,
+            String::from("Integer was not an integer?"),
+            Colour::RED,
+        );
+    };
+
+    let submission = match bot.store.submission_summary(id).await {
+        Ok(Some(submission)) => submission,
+        Ok(None) => {
+            return reply(
+                command,
+                format!("There is no submission with ID: {}", id),
+                Colour::RED,
+            )
+        }
+        Err(err) => return reply(command, err.to_string(), Colour::RED),
+    };
+
+    let mut embed = submission_embed(&submission, command.user.avatar_url().unwrap_or_default());
+    embed.description(format!(
+        "Are you sure you want to {} this submission?",
+        action
+    ));
+
+    let created = Utc::now().timestamp();
+    let confirm_button_id = format!("lbadmin_{}_{}_{}", action, id, created);
+    let cancel_button_id = format!("lbadmin_cancel_{}_{}", id, created);
+
+    let mut message = CreateInteractionResponseData::default();
+    message.add_embed(embed);
+    message.components(|component| {
+        component.create_action_row(|action_row| {
+            action_row
+                .create_button(|b| {
+                    b.custom_id(&confirm_button_id)
+                        .label(capitalize(action))
+                        .style(if action == "revalidate" {
+                            ButtonStyle::Success
+                        } else {
+                            ButtonStyle::Danger
+                        })
+                })
+                .create_button(|b| {
+                    b.custom_id(&cancel_button_id)
+                        .label("Cancel")
+                        .style(ButtonStyle::Secondary)
+                })
+        })
+    });
+    message
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// How long the confirmation buttons can be used, in seconds
+const CONFIRMATION_TTL: i64 = 5 * 60;
+
+/// Handles the confirmation buttons, their custom IDs look like
+/// `lbadmin_{action}_{submission id}_{unix time of the confirmation}`
+pub async fn handle_component(ctx: &Context, bot: &Bot, component: &MessageComponentInteraction) {
+    let mut info = component.data.custom_id.split('_').skip(1);
+    let (Some(action), Some(Ok(id))) = (info.next(), info.next().map(|id| id.parse::<i64>()))
+    else {
+        return;
+    };
+    // Buttons without a time are from before it was added, so they count as expired
+    let expired = info
+        .next()
+        .and_then(|created| created.parse::<i64>().ok())
+        .is_none_or(|created| Utc::now().timestamp() - created > CONFIRMATION_TTL);
+
+    if !bot.config.owner_ids.contains(component.user.id.as_u64()) {
+        respond_privately(ctx, component, "You're not allowed to do that.").await;
+        return;
+    }
+
+    let (result, done) = match action {
+        "invalidate" | "revalidate" | "delete" if expired => {
+            update_confirmation(
+                ctx,
+                component,
+                format!(
+                    "This confirmation expired, submission {} wasn't changed. Run the command again.",
+                    id
+                ),
+                Colour::RED,
+            )
+            .await;
+            return;
+        }
+        "invalidate" => (
+            bot.store.set_submission_validity(id, false).await,
+            "Invalidated",
+        ),
+        "revalidate" => (
+            bot.store.set_submission_validity(id, true).await,
+            "Revalidated",
+        ),
+        "delete" => (bot.store.delete_submission(id).await, "Deleted"),
+        "cancel" => {
+            update_confirmation(
+                ctx,
+                component,
+                format!("Cancelled, submission {} wasn't changed.", id),
+                Colour::from_rgb(106, 86, 246),
+            )
+            .await;
+            return;
+        }
+        _ => {
+            respond_privately(ctx, component, "Unknown action").await;
+            return;
+        }
+    };
+    if let Ok(affected) = result {
+        if affected > 0 {
+            audit::record(
+                ctx,
+                bot,
+                *component.user.id.as_u64(),
+                action,
+                &id.to_string(),
+            );
+        }
+    }
+    let (content, colour) = match result {
+        Ok(0) => (
+            format!("There is no submission with ID: {}", id),
+            Colour::RED,
+        ),
+        Ok(affected) => (
+            format!(
+                "{} submission with ID: {} ({} row{} affected)",
+                done,
+                id,
+                affected,
+                if affected == 1 { "" } else { "s" }
+            ),
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => (err.to_string(), Colour::RED),
+    };
+    update_confirmation(ctx, component, content, colour).await;
+}
+
+/// Replaces the confirmation with the outcome, without its buttons
+async fn update_confirmation(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    content: String,
+    colour: Colour,
+) {
+    let mut embed = CreateEmbed::default();
+    embed.author(|a| {
+        a.name(&component.user.name)
+            .icon_url(component.user.avatar_url().unwrap_or_default())
+    });
+    embed.colour(colour);
+    embed.description(content);
+    embed.timestamp(Utc::now().to_rfc3339());
+
+    if let Err(why) = component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|m| m.set_embed(embed).components(|c| c))
+        })
+        .await
+    {
+        println!("Cannot respond to button: {}", why);
+    }
+}
+
+/// Answers only the member that pressed the button, leaving the confirmation as it is
+async fn respond_privately(ctx: &Context, component: &MessageComponentInteraction, content: &str) {
+    if let Err(why) = component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|m| m.ephemeral(true).content(content))
+        })
+        .await
+    {
+        println!("Cannot respond to button: {}", why);
+    }
+}
+
+async fn submissions_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let game = match find_option(option, "game") {
+        Some(CommandDataOptionValue::String(code)) => match bot.games().by_code(code) {
+            Some(game) => Some(game.name.clone()),
+            None => {
+                return reply(
+                    command,
+                    String::from("That game doesn't exist (anymore)."),
+                    Colour::RED,
+                )
+            }
+        },
+        _ => None,
+    };
+
+    match bot.store.submission_summaries(game.as_deref()).await {
+        Ok(submissions) if submissions.is_empty() => reply(
+            command,
+            String::from("There are no submissions yet."),
+            Colour::RED,
+        ),
+        Ok(submissions) => submissions_to_response(
+            bot,
+            *command.user.id.as_u64(),
+            command.user.avatar_url().unwrap_or_default(),
+            submissions,
+        ),
+        Err(err) => reply(command, err.to_string(), Colour::RED),
+    }
+}
+
+fn submissions_to_response(
+    bot: &Bot,
+    owner: u64,
+    avatar_url: String,
+    submissions: Vec<SubmissionSummary>,
+) -> CreateInteractionResponseData<'static> {
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Submissions: {}", submissions.len()))
+            .icon_url(avatar_url)
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+
+    let pages: Vec<String> = submissions
+        .chunks(10)
+        .map(|chunk| {
+            let mut s = String::from("Submissions, newest first:");
+            for submission in chunk {
+                s += &submission.get_summary_string();
+            }
+            s
+        })
+        .collect();
+
+    let mut message = CreateInteractionResponseData::default();
+    paginate(bot, owner, &mut message, description_pages(&embed, pages));
+    message
+}
+
+async fn audit_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+) -> CreateInteractionResponseData<'a> {
+    let entries = match bot.store.audit_entries().await {
+        Ok(entries) if entries.is_empty() => {
+            return reply(
+                command,
+                String::from("Nothing has been logged yet."),
+                Colour::RED,
+            )
+        }
+        Ok(entries) => entries,
+        Err(err) => return reply(command, err.to_string(), Colour::RED),
+    };
+
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Entries: {}", entries.len()))
+            .icon_url(command.user.avatar_url().unwrap_or_default())
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+
+    let pages: Vec<String> = entries
+        .chunks(10)
+        .map(|chunk| {
+            let mut s = String::from("Audit log, newest first:");
+            for entry in chunk {
+                s += &entry.get_audit_string();
+            }
+            s
+        })
+        .collect();
+
+    let mut message = CreateInteractionResponseData::default();
+    paginate(
+        bot,
+        *command.user.id.as_u64(),
+        &mut message,
+        description_pages(&embed, pages),
+    );
+    message
+}
+
+async fn add_game_command<'a>(
+    ctx: &Context,
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let string_option = |name: &str| match find_option(option, name) {
+        Some(CommandDataOptionValue::String(value)) => Some(value.to_owned()),
+        _ => None,
+    };
+    let (Some(code), Some(name), Some(score_unit), Some(direction)) = (
+        string_option("code"),
+        string_option("name"),
+        string_option("unit"),
+        string_option("direction"),
+    ) else {
+        return reply(
+            command,
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+
+    let game = Game {
+        code,
+        name,
+        score_unit,
+        descending: direction != "asc",
+        active: true,
+    };
+    match GameRegistry::add(&bot.db, &game).await {
+        Ok(_) => {
+            let _ = bot.reload_games().await;
+            // Its submissions may be in the database already
+            bot.store.refresh_game(&game.name).await;
+            audit::record(ctx, bot, *command.user.id.as_u64(), "add game", &game.code);
+            reply(
+                command,
+                format!(
+                    "Added {} ({}). Run /lbadmin sync to update the commands.",
+                    game.name, game.code
+                ),
+                Colour::from_rgb(106, 86, 246),
+            )
+        }
+        Err(err) => reply(command, err.to_string(), Colour::RED),
+    }
+}
+
+async fn retire_game_command<'a>(
+    ctx: &Context,
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(CommandDataOptionValue::String(code)) = find_option(option, "game") else {
+        return reply(
+            command,
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+
+    match GameRegistry::retire(&bot.db, code).await {
+        Ok(0) => reply(
+            command,
+            format!("There is no game with code: {}", code),
+            Colour::RED,
+        ),
+        Ok(_) => {
+            let _ = bot.reload_games().await;
+            audit::record(ctx, bot, *command.user.id.as_u64(), "retire game", code);
+            reply(
+                command,
+                format!(
+                    "Retired {}. Run /lbadmin sync to update the commands.",
+                    code
+                ),
+                Colour::from_rgb(106, 86, 246),
+            )
+        }
+        Err(err) => reply(command, err.to_string(), Colour::RED),
+    }
+}
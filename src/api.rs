@@ -0,0 +1,133 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    commands::LeaderboardRow,
+    games::{Game, GameRegistry},
+    queries::{self, Submission},
+};
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[derive(Clone)]
+struct ApiState {
+    db: Pool<Postgres>,
+    games: Arc<Mutex<GameRegistry>>,
+}
+
+impl ApiState {
+    fn game(&self, code: &str) -> Option<Game> {
+        self.games
+            .lock()
+            .ok()
+            .and_then(|games| games.by_code(code).cloned())
+    }
+}
+
+#[derive(Deserialize)]
+struct Range {
+    from: Option<i32>,
+    to: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct SubmissionResponse {
+    #[serde(flatten)]
+    submission: Submission,
+    leaderboard: Vec<LeaderboardRow>,
+}
+
+/// Serves the read-only JSON API next to the Discord client
+pub async fn serve(address: SocketAddr, db: Pool<Postgres>, games: Arc<Mutex<GameRegistry>>) {
+    let app = Router::new()
+        .route("/games", get(all_games))
+        .route("/games/:game/leaderboard", get(game_leaderboard))
+        .route("/players/:name", get(player_leaderboards))
+        .route("/submissions/:id", get(submission))
+        .with_state(ApiState { db, games });
+
+    println!("API listening on {}", address);
+    if let Err(why) = axum::Server::bind(&address)
+        .serve(app.into_make_service())
+        .await
+    {
+        println!("API error: {:?}", why);
+    }
+}
+
+fn database_error(err: sqlx::Error) -> (StatusCode, String) {
+    println!("{}", err);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        String::from("An error occurred trying to fetch the leaderboards"),
+    )
+}
+
+async fn all_games(State(state): State<ApiState>) -> ApiResult<Vec<Game>> {
+    let games = state
+        .games
+        .lock()
+        .map(|games| games.all().to_vec())
+        .unwrap_or_default();
+    Ok(Json(games))
+}
+
+async fn game_leaderboard(
+    State(state): State<ApiState>,
+    Path(code): Path<String>,
+    Query(range): Query<Range>,
+) -> ApiResult<Vec<LeaderboardRow>> {
+    let Some(game) = state.game(&code) else {
+        return Err((StatusCode::NOT_FOUND, format!("Unknown game: {}", code)));
+    };
+
+    let players = queries::latest_game_leaderboard(&state.db, &game.name)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .filter(|row| range.from.is_none_or(|from| row.position >= from))
+        .filter(|row| range.to.is_none_or(|to| row.position <= to))
+        .collect();
+    Ok(Json(players))
+}
+
+async fn player_leaderboards(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> ApiResult<Vec<LeaderboardRow>> {
+    let leaderboards = queries::latest_player_leaderboards(&state.db, &name)
+        .await
+        .map_err(database_error)?;
+    Ok(Json(leaderboards))
+}
+
+async fn submission(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> ApiResult<SubmissionResponse> {
+    let Some(submission) = queries::submission(&state.db, id)
+        .await
+        .map_err(database_error)?
+    else {
+        return Err((StatusCode::NOT_FOUND, format!("Unknown submission: {}", id)));
+    };
+
+    let leaderboard = queries::submission_leaderboard(&state.db, &submission.game, id)
+        .await
+        .map_err(database_error)?;
+    Ok(Json(SubmissionResponse {
+        submission,
+        leaderboard,
+    }))
+}
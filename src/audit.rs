@@ -0,0 +1,34 @@
+use serenity::{model::prelude::ChannelId, prelude::Context};
+
+use crate::Bot;
+
+/// Records an administrative action, and mirrors it to the audit channel when one is configured.
+/// Both happen in the background, so the reply doesn't wait for them
+pub fn record(ctx: &Context, bot: &Bot, actor: u64, action: &str, target: &str) {
+    let http = ctx.http.clone();
+    let store = bot.store.clone();
+    let audit_channel_id = bot.config.audit_channel_id;
+    let (action, target) = (action.to_owned(), target.to_owned());
+
+    tokio::spawn(async move {
+        if let Err(why) = store
+            .insert_audit_entry(actor as i64, &action, &target)
+            .await
+        {
+            println!("Cannot save audit entry: {}", why);
+        }
+
+        let Some(channel_id) = audit_channel_id else {
+            return;
+        };
+        if let Err(why) = ChannelId(channel_id)
+            .send_message(&http, |m| {
+                m.content(format!("<@{}> {} {}", actor, action, target))
+                    .allowed_mentions(|mentions| mentions.empty_parse())
+            })
+            .await
+        {
+            println!("Cannot mirror audit entry: {}", why);
+        }
+    });
+}
@@ -1,21 +1,60 @@
-use std::vec;
+use std::{borrow::Cow, vec};
 
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serenity::{
-    builder::{CreateApplicationCommand, CreateEmbed, CreateInteractionResponseData},
+    builder::{
+        CreateApplicationCommand, CreateApplicationCommandOption, CreateEmbed,
+        CreateInteractionResponseData, EditInteractionResponse,
+    },
     model::prelude::{
         command::CommandOptionType,
-        interaction::application_command::{
-            ApplicationCommandInteraction, CommandDataOption, CommandDataOptionValue,
+        interaction::{
+            application_command::{
+                ApplicationCommandInteraction, CommandDataOption, CommandDataOptionValue,
+            },
+            autocomplete::AutocompleteInteraction,
         },
+        AttachmentType, ChannelType, User,
     },
+    prelude::Context,
     utils::Colour,
 };
 use sqlx::FromRow;
 
-use crate::{common::default_embed_from_content, Bot};
+use crate::{
+    audit,
+    common::{default_embed_from_content, find_option},
+    diff::LeaderboardDiff,
+    export::ExportFormat,
+    games::{Game, GameRegistry},
+    paginator::{self, description_pages, paginate, PaginatorSession},
+    render::{render_leaderboard, ImageRow, IMAGE_NAME},
+    similarity::closest,
+    submission::parse_submission,
+    Bot,
+};
 
 #[derive(FromRow)]
+pub struct HistoryRow {
+    pub unix_time_stamp: i64,
+    pub position: Option<i32>,
+    pub score: Option<i32>,
+}
+
+impl HistoryRow {
+    pub fn get_history_string(&self, score_unit: &str) -> String {
+        match (self.position, self.score) {
+            (Some(position), Some(score)) => format!(
+                "\n- {} [{}]: {} {}",
+                self.unix_time_stamp, position, score, score_unit
+            ),
+            _ => format!("\n- {} ~~not on the leaderboard~~", self.unix_time_stamp),
+        }
+    }
+}
+
+#[derive(FromRow, Serialize, Clone)]
 pub struct LeaderboardRow {
     pub player: String,
     pub position: i32,
@@ -25,47 +64,138 @@ pub struct LeaderboardRow {
 }
 
 impl LeaderboardRow {
-    pub fn get_leaderboard_string(&self) -> String {
+    pub fn get_leaderboard_string(&self, score_unit: &str) -> String {
+        format!(
+            "\n- {} [{}]: {} {}",
+            self.game, self.position, self.score, score_unit
+        )
+    }
+
+    pub fn get_player_string(&self, score_unit: &str) -> String {
         format!(
             "\n- {} [{}]: {} {}",
-            self.game,
+            self.player.replace('_', "\\_"),
             self.position,
             self.score,
-            self.game_to_score_kind()
+            score_unit
         )
     }
 
-    pub fn get_player_string(&self) -> String {
+    pub fn get_highlighted_player_string(&self, score_unit: &str) -> String {
         format!(
-            "\n- {} [{}]: {} {}",
-            self.player.replace("_", "\\_"),
+            "\n- **{} [{}]: {} {}**",
+            self.player.replace('_', "\\_"),
             self.position,
             self.score,
-            self.game_to_score_kind()
+            score_unit
         )
     }
+}
 
-    fn game_to_score_kind(&self) -> String {
-        match self.game.as_str() {
-            "Team EggWars" | "Solo SkyWars" | "Team EggWars Season 2" | "Lucky Islands" => {
-                String::from("wins")
-            }
-            "Free For All" => String::from("kills"),
-            "Parkour" | "Snowman Survival" => String::from("medals"),
-            &_ => String::from("unknown"),
+/// Rows per page when `page_size` isn't given
+const DEFAULT_PAGE_SIZE: i64 = 10;
+
+/// Which positions of a game leaderboard to show, kept with the paginator for its Find player
+/// control
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BoardQuery {
+    /// The code of the game
+    pub game: String,
+    pub from: i64,
+    /// No upper bound when missing
+    pub to: Option<i64>,
+    pub page_size: i64,
+}
+
+impl BoardQuery {
+    /// Empty for the whole leaderboard, otherwise starts with a space
+    fn range_description(&self) -> String {
+        match (self.from, self.to) {
+            (from, Some(to)) => format!(" between {} and {}", from, to),
+            (1, None) => String::new(),
+            (from, None) => format!(" from place {} on", from),
         }
     }
+
+    fn page_size(&self) -> usize {
+        self.page_size.max(1) as usize
+    }
+}
+
+/// Which part of a game leaderboard to show, and how
+struct BoardView {
+    query: BoardQuery,
+    /// Opens at the page of this player, with their row in bold
+    player: Option<String>,
+    as_image: bool,
+}
+
+/// Whether the response is deferred before running the command, for sub commands that can take
+/// longer than the three seconds Discord waits for a response
+pub fn is_deferred(command: &ApplicationCommandInteraction) -> bool {
+    command
+        .data
+        .options
+        .first()
+        .is_some_and(|sub_option| sub_option.name == "submit")
+}
+
+/// What the edit of a deferred response can carry over from a regular one
+const DEFERRED_KEYS: [&str; 3] = ["content", "embeds", "components"];
+
+/// Turns the response of a deferred command into the edit that replaces its placeholder. Anything
+/// the edit can't carry, like files or flags, is an error instead of being left out
+pub fn deferred_response(
+    response: &CreateInteractionResponseData,
+) -> Result<EditInteractionResponse, String> {
+    if !response.1.is_empty() {
+        return Err(String::from("files can't be added to a deferred response"));
+    }
+    if let Some(key) = response.0.keys().find(|key| !DEFERRED_KEYS.contains(key)) {
+        return Err(format!("`{}` can't be set on a deferred response", key));
+    }
+
+    let mut edit = EditInteractionResponse::default();
+    for (key, value) in &response.0 {
+        edit.0.insert(key, value.clone());
+    }
+    Ok(edit)
 }
 
 pub async fn run<'a>(
+    ctx: &Context,
     bot: &Bot,
     command: &'a ApplicationCommandInteraction,
 ) -> CreateInteractionResponseData<'a> {
     let options = &command.data.options;
-    if let Some(sub_option) = options.get(0) {
+    if let Some(sub_option) = options.first() {
         match sub_option.name.as_str() {
-            "all" => player_command(bot, &command, &sub_option.options).await,
-            "game" => leaderboards_command(bot, &command, &sub_option.options).await,
+            "all" => player_command(bot, command, &sub_option.options).await,
+            "game" => leaderboards_command(bot, command, &sub_option.options).await,
+            "history" => history_command(bot, command, &sub_option.options).await,
+            "diff" => diff_command(bot, command, &sub_option.options).await,
+            "compare" => compare_command(bot, command, &sub_option.options).await,
+            "follow" => follow_command(bot, command, &sub_option.options).await,
+            "unfollow" => unfollow_command(bot, command, &sub_option.options).await,
+            "following" => following_command(bot, command).await,
+            "announce" => announce_command(bot, command, &sub_option.options).await,
+            "link" => link_command(bot, command, &sub_option.options).await,
+            "export" => match sub_option.options.first() {
+                Some(group_option) if group_option.name == "game" => {
+                    export_game_command(bot, command, &group_option.options).await
+                }
+                Some(group_option) if group_option.name == "player" => {
+                    export_player_command(bot, command, &group_option.options).await
+                }
+                _ => default_embed_from_content(
+                    &command.user.name,
+                    &command.user.avatar_url().unwrap_or_default(),
+                    String::from("Not a valid sub command. What happened here?"),
+                    Colour::RED,
+                ),
+            },
+            "unlink" => unlink_command(bot, command).await,
+            "submit" => submit_command(ctx, bot, command, &sub_option.options).await,
             _ => default_embed_from_content(
                 &command.user.name,
                 &command.user.avatar_url().unwrap_or_default(),
@@ -83,7 +213,41 @@ pub async fn run<'a>(
     }
 }
 
-pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+/// Suggests player names for whichever `player` option is being typed in
+pub async fn autocomplete(bot: &Bot, interaction: &AutocompleteInteraction) -> Vec<String> {
+    let Some(focused) = interaction
+        .data
+        .options
+        .iter()
+        .flat_map(|sub_option| sub_option.options.iter())
+        // Options of a sub command in a group are one level deeper
+        .flat_map(|option| std::iter::once(option).chain(option.options.iter()))
+        .find(|option| option.focused)
+    else {
+        return vec![];
+    };
+    if !focused.name.starts_with("player") {
+        return vec![];
+    }
+
+    let prefix = focused
+        .value
+        .as_ref()
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+    match bot.store.player_names_starting_with(prefix, 25).await {
+        Ok(names) => names,
+        Err(err) => {
+            println!("{}", err);
+            vec![]
+        }
+    }
+}
+
+pub fn register<'a>(
+    command: &'a mut CreateApplicationCommand,
+    games: &GameRegistry,
+) -> &'a mut CreateApplicationCommand {
     command
         .name("leaderboard")
         .description("CubeCraft's leaderboard info")
@@ -95,11 +259,21 @@ pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicatio
                 .create_sub_option(|suboption| {
                     suboption
                         .name("player")
-                        .description("The player")
+                        .description("The player, defaults to your linked player")
                         .kind(CommandOptionType::String)
-                        .required(true)
+                        .required(false)
                         .min_length(2)
                         .max_length(16)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("format")
+                        .description("Show the result as text or as an image")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Text", "text")
+                        .add_string_choice("Image", "image")
+                        .required(false)
                 })
         })
         .create_option(|option| {
@@ -112,24 +286,286 @@ pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicatio
                         .name("game")
                         .description("The game")
                         .kind(CommandOptionType::String)
-                        .add_string_choice("Team EggWars", "tew")
-                        .add_string_choice("Team EggWars Season 2", "tew2")
-                        .add_string_choice("Solo SkyWars", "s_sw")
-                        .add_string_choice("Solo Lucky Islands", "s_li")
-                        .add_string_choice("Free For All", "ffa")
-                        .add_string_choice("Parkour", "parkour")
-                        .add_string_choice("Snowman Survival", "ss")
-                        .required(true)
+                        .required(true);
+                    games.add_choices(suboption)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("from")
+                        .description("From place, defaults to 1")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                        .required(false)
                 })
                 .create_sub_option(|suboption| {
                     suboption
-                        .name("place")
-                        .description("From place, and 10 more")
+                        .name("to")
+                        .description("Up to and including place, defaults to the whole leaderboard")
                         .kind(CommandOptionType::Integer)
                         .min_int_value(1)
-                        .max_int_value(200)
                         .required(false)
                 })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("page_size")
+                        .description("Players per page, defaults to 10")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(5)
+                        .max_int_value(25)
+                        .required(false)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player")
+                        .description("Open at the page of this player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("format")
+                        .description(
+                            "Show the result as text or as an image of a single page, the first or the player's",
+                        )
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Text", "text")
+                        .add_string_choice("Image (single page)", "image")
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("history")
+                .description("Get a player's positions on a game over time")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player")
+                        .description("The player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("The game")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    games.add_choices(suboption)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("diff")
+                .description("See what changed between two submissions of a game")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("The game")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    games.add_choices(suboption)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("from")
+                        .description("The older submission ID, defaults to the one before `to`")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("to")
+                        .description("The newer submission ID, defaults to the latest")
+                        .kind(CommandOptionType::Integer)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("compare")
+                .description("Compare two players head-to-head on every game")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player_a")
+                        .description("The first player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player_b")
+                        .description("The second player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("follow")
+                .description("Get notified when a player's position changes")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player")
+                        .description("The player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("deliver")
+                        .description("Where to send the notifications, defaults to a DM")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("DM", "dm")
+                        .add_string_choice("This channel", "here")
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("unfollow")
+                .description("Stop getting notified about a player")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player")
+                        .description("The player, or `me`")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                        .set_autocomplete(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("following")
+                .description("List the players you're following")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("export")
+                .description("Download leaderboards as a file")
+                .kind(CommandOptionType::SubCommandGroup)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("Every player on a game's leaderboard")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|o| {
+                            o.name("game")
+                                .description("The game")
+                                .kind(CommandOptionType::String)
+                                .required(true);
+                            games.add_choices(o)
+                        })
+                        .create_sub_option(|o| export_format_option(o))
+                        .create_sub_option(|o| {
+                            o.name("submission")
+                                .description("The submission ID, defaults to the latest")
+                                .kind(CommandOptionType::Integer)
+                                .required(false)
+                        })
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("player")
+                        .description("Every leaderboard a player is on")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|o| export_format_option(o))
+                        .create_sub_option(|o| {
+                            o.name("player")
+                                .description("The player, defaults to your linked player")
+                                .kind(CommandOptionType::String)
+                                .required(false)
+                                .min_length(2)
+                                .max_length(16)
+                                .set_autocomplete(true)
+                        })
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("link")
+                .description("Link your Discord account to your player name")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("name")
+                        .description("Your player name")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .min_length(2)
+                        .max_length(16)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("unlink")
+                .description("Remove the link between your Discord account and player name")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("announce")
+                .description("Post new snapshots of a game in a channel (Manage Server only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("The game")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    games.add_choices(suboption)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("channel")
+                        .description("Where to post them, leave empty to stop announcing")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("submit")
+                .description("Submit a new leaderboard snapshot (trusted roles only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("game")
+                        .description("The game")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    games.add_choices(suboption)
+                })
+                .create_sub_option(|suboption| {
+                    suboption
+                        .name("file")
+                        .description("A .csv (player,position,score) or .json file")
+                        .kind(CommandOptionType::Attachment)
+                        .required(true)
+                })
         })
 }
 
@@ -139,67 +575,71 @@ async fn leaderboards_command<'a>(
     option: &'a [CommandDataOption],
 ) -> CreateInteractionResponseData<'a> {
     let game = option
-        .get(0)
+        .first()
         .expect("There should be a game")
         .resolved
         .as_ref()
         .expect("Expected String");
 
-    let lower = if let None = option.get(1) {
-        &1
+    let integer_option = |name: &str| match find_option(option, name) {
+        Some(CommandDataOptionValue::Integer(i)) => Some(*i),
+        _ => None,
+    };
+    let from = integer_option("from").unwrap_or(1);
+    let to = integer_option("to");
+    if to.is_some_and(|to| to < from) {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("`to` can't be a place before `from`."),
+            Colour::RED,
+        );
+    }
+    let page_size = integer_option("page_size").unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let player = if find_option(option, "player").is_some() {
+        match resolve_player(bot, command, option, "player").await {
+            Ok(player_name) => Some(player_name),
+            Err(response) => return response,
+        }
     } else {
-        if let Some(CommandDataOptionValue::Integer(i)) = option.get(1).unwrap().resolved.as_ref() {
-            i
-        } else {
+        None
+    };
+
+    if let CommandDataOptionValue::String(game) = game {
+        let Some(game) = bot.games().by_code(game).cloned() else {
             return default_embed_from_content(
                 &command.user.name,
                 &command.user.avatar_url().unwrap_or_default(),
-                String::from("Integer was not an integer?"),
+                String::from("That game doesn't exist (anymore)."),
                 Colour::RED,
             );
-        }
-    };
-    let upper = 200;
+        };
 
-    if let CommandDataOptionValue::String(game) = game {
-        match sqlx::query_as::<_, LeaderboardRow>(
-            "
-            SELECT 
-                player,position,score,game,unix_time_stamp
-            FROM
-                leaderboards
-            WHERE
-                game = $1
-            AND
-                unix_time_stamp
-            = (SELECT
-                    MAX(unix_time_stamp)
-                FROM
-                    submissions
-                WHERE
-                    valid = TRUE
-                AND
-                    game = $1)
-            ORDER BY
-                position
-            ASC;",
-        )
-        .bind(leaderboard_value_to_database_name(game.to_owned()))
-        .bind(lower)
-        .bind(upper)
-        .fetch_all(&bot.db)
-        .await
+        let query = BoardQuery {
+            game: game.code.clone(),
+            from,
+            to,
+            page_size,
+        };
+        match bot
+            .store
+            .latest_game_leaderboard_between(&game.name, from, to.unwrap_or(i64::MAX))
+            .await
         {
             Ok(players) => players_to_response(
                 bot,
-                command.user.avatar_url().unwrap_or_default(),
+                &command.user,
                 players,
-                game.to_owned(),
-                lower.to_owned(),
-                upper,
+                &game,
+                BoardView {
+                    query,
+                    player,
+                    as_image: wants_image(option),
+                },
             ),
             Err(err) => {
-                println!("{}", err.to_string());
+                println!("{}", err);
                 default_embed_from_content(
                     &command.user.name,
                     &command.user.avatar_url().unwrap_or_default(),
@@ -219,84 +659,184 @@ async fn leaderboards_command<'a>(
 
 fn players_to_response(
     bot: &Bot,
-    avatar_url: String,
+    user: &User,
     players: Vec<LeaderboardRow>,
-    game_name: String,
-    lower: i64,
-    upper: i64,
+    game: &Game,
+    view: BoardView,
 ) -> CreateInteractionResponseData<'static> {
-    let mut embed = CreateEmbed::default();
-    embed.footer(|f| {
-        f.text(format!(
-            "Submission ID: {}",
-            players
-                .get(0)
-                .and_then(|row| Some(row.unix_time_stamp.to_string()))
-                .unwrap_or_else(|| String::from("unknown"))
-        ))
-        .icon_url(avatar_url)
-    });
-
-    let now = Utc::now();
-    embed.timestamp(now.to_rfc3339());
+    let BoardView {
+        query,
+        player,
+        as_image,
+    } = view;
+    let mut embed = board_embed(user, &players);
 
     let mut message = CreateInteractionResponseData::default();
 
-    if players.len() == 0 {
+    let index = player
+        .as_deref()
+        .and_then(|player_name| player_page(&players, query.page_size(), player_name));
+
+    if players.is_empty() {
         embed.colour(Colour::RED);
-        embed.description(String::from(format!(
-            "**{}** currently doesn't have any players on it between {} and {}.",
-            game_name, lower, upper
-        )));
-    } else {
+        embed.description(format!(
+            "**{}** currently doesn't have any players on it{}.",
+            game.name,
+            query.range_description()
+        ));
+    } else if let (Some(player_name), None) = (&player, index) {
+        embed.colour(Colour::RED);
+        embed.description(format!(
+            "**{}** isn't on the {} leaderboard{}.",
+            player_name.replace('_', "\\_"),
+            game.name,
+            query.range_description()
+        ));
+    } else if as_image {
+        // Attachments can't be flipped through, so the image only ever shows this one page
         embed.colour(Colour::from_rgb(106, 86, 246));
 
-        let mut pages: Vec<String> = vec![];
-        let pretty_name = leaderboard_value_to_database_name(game_name);
+        let page = players
+            .chunks(query.page_size())
+            .nth(index.unwrap_or_default())
+            .unwrap_or_default();
+        let rows: Vec<ImageRow> = page
+            .iter()
+            .map(|row| ImageRow {
+                position: row.position,
+                name: &row.player,
+                score: row.score,
+                score_unit: &game.score_unit,
+            })
+            .collect();
+        let (low, up) = page_bounds(page);
+        attach_image(
+            &mut embed,
+            &mut message,
+            &format!("{} #{}-{}", game.name, low, up),
+            &rows,
+            players[0].unix_time_stamp,
+        );
+    } else {
+        embed.colour(Colour::from_rgb(106, 86, 246));
 
-        for low in (lower..200).step_by(10) {
-            if low > 200 {
-                break;
-            }
-            let up = if low + 9 < 200 { low + 9 } else { 200 };
-            let mut s = String::from(format!(
-                "Players on {} between {} and {}:",
-                pretty_name, low, up
-            ));
-            for row in &players[((low - 1) as usize)..(up as usize)] {
-                s += &row.get_player_string();
-            }
-            pages.push(s);
-        }
-        embed.description(&pages[0]);
-        if let Ok(mut data) = bot.running_paginator.lock() {
-            let next_button_id = format!("{}_next_lb_{}", now.timestamp_millis(), 1);
-
-            message.components(|component| {
-                component.create_action_row(|action_row| {
-                    action_row.create_button(|b| b.custom_id(&next_button_id).emoji('▶'))
-                })
-            });
-
-            data.insert(now.timestamp_millis() as u64, pages);
-        }
+        let pages = board_pages(&players, game, query.page_size(), player.as_deref());
+        let mut session =
+            PaginatorSession::new(*user.id.as_u64(), description_pages(&embed, pages));
+        session.find_player = Some(query);
+        paginator::show(bot, session, index.unwrap_or_default(), &mut message);
+        return message;
     }
     message.add_embed(embed);
     message
 }
 
-fn leaderboard_value_to_database_name(game: String) -> String {
-    match game.as_str() {
-        "tew" => String::from("Team EggWars"),
-        "tew2" => String::from("Team EggWars Season 2"),
-        "s_sw" => String::from("Solo SkyWars"),
-        "s_li" => String::from("Lucky Islands"),
-        "ffa" => String::from("Free For All"),
-        "parkour" => String::from("Parkour"),
-        "ss" => String::from("Snowman Survival"),
+/// The footer and timestamp every response about a game leaderboard has
+fn board_embed(user: &User, players: &[LeaderboardRow]) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!(
+            "Submission ID: {}",
+            players
+                .first()
+                .map(|row| row.unix_time_stamp.to_string())
+                .unwrap_or_else(|| String::from("unknown"))
+        ))
+        .icon_url(user.avatar_url().unwrap_or_default())
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed
+}
+
+/// `page_size` players per page, however many rows the leaderboard has
+fn board_pages(
+    players: &[LeaderboardRow],
+    game: &Game,
+    page_size: usize,
+    highlight: Option<&str>,
+) -> Vec<String> {
+    players
+        .chunks(page_size)
+        .map(|page| {
+            let (low, up) = page_bounds(page);
+            players_page(page, game, low, up, highlight)
+        })
+        .collect()
+}
+
+/// The first and last position on a page
+fn page_bounds(page: &[LeaderboardRow]) -> (i64, i64) {
+    (
+        page.first().map_or(0, |row| row.position as i64),
+        page.last().map_or(0, |row| row.position as i64),
+    )
+}
 
-        &_ => String::from("Unknown"),
+/// The index of the page a player is on
+fn player_page(players: &[LeaderboardRow], page_size: usize, player_name: &str) -> Option<usize> {
+    players
+        .iter()
+        .position(|row| row.player.eq_ignore_ascii_case(player_name))
+        .map(|row_index| row_index / page_size)
+}
+
+/// The pages of the latest leaderboard of a game, and the index of the page the player is on.
+/// Backs the Find player control of the paginator, the error is shown to the user as is
+pub async fn find_player_pages(
+    bot: &Bot,
+    user: &User,
+    query: &BoardQuery,
+    player_name: &str,
+) -> Result<(Vec<CreateEmbed>, usize), String> {
+    let Some(game) = bot.games().by_code(&query.game).cloned() else {
+        return Err(String::from("That game doesn't exist (anymore)."));
+    };
+    let players = match bot
+        .store
+        .latest_game_leaderboard_between(&game.name, query.from, query.to.unwrap_or(i64::MAX))
+        .await
+    {
+        Ok(players) => players,
+        Err(err) => {
+            println!("{}", err);
+            return Err(String::from(
+                "An error occurred trying to fetch the leaderboards. Contact Fesa if this persists",
+            ));
+        }
+    };
+
+    let Some(index) = player_page(&players, query.page_size(), player_name) else {
+        return Err(format!(
+            "**{}** isn't on the {} leaderboard{}.",
+            player_name.replace('_', "\\_"),
+            game.name,
+            query.range_description()
+        ));
+    };
+
+    let mut embed = board_embed(user, &players);
+    embed.colour(Colour::from_rgb(106, 86, 246));
+    let pages = board_pages(&players, &game, query.page_size(), Some(player_name));
+    Ok((description_pages(&embed, pages), index))
+}
+
+/// The rows of `highlight` are shown in bold
+pub fn players_page(
+    players: &[LeaderboardRow],
+    game: &Game,
+    low: i64,
+    up: i64,
+    highlight: Option<&str>,
+) -> String {
+    let mut s = format!("Players on {} between {} and {}:", game.name, low, up);
+    for row in players {
+        if highlight.is_some_and(|player_name| row.player.eq_ignore_ascii_case(player_name)) {
+            s += &row.get_highlighted_player_string(&game.score_unit);
+        } else {
+            s += &row.get_player_string(&game.score_unit);
+        }
     }
+    s
 }
 
 async fn player_command<'a>(
@@ -304,102 +844,1176 @@ async fn player_command<'a>(
     command: &'a ApplicationCommandInteraction,
     option: &'a [CommandDataOption],
 ) -> CreateInteractionResponseData<'a> {
-    let player = option
-        .get(0)
-        .expect("There should be a player")
-        .resolved
-        .as_ref()
-        .expect("Expected String");
+    let player_name = match resolve_player(bot, command, option, "player").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
 
-    if let CommandDataOptionValue::String(player_name) = player {
-        match sqlx::query_as::<_, LeaderboardRow>(
-            "
-                SELECT 
-                    player,position,score,game,unix_time_stamp
-                FROM
-                    leaderboards
-                WHERE
-                    (game, unix_time_stamp)
-                IN (SELECT
-                        game, MAX(unix_time_stamp)
-                    FROM
-                        submissions
-                    WHERE
-                        valid = TRUE
-                    GROUP BY
-                        game)
-                AND
-                    player = $1
-                ORDER BY
-                    position
-                ASC;",
-        )
-        .bind(player_name)
-        .fetch_all(&bot.db)
-        .await
+    match bot.store.latest_player_leaderboards(&player_name).await {
+        Ok(leaderboards) if leaderboards.is_empty() => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            missing_player_description(bot, &player_name).await,
+            Colour::RED,
+        ),
+        Ok(leaderboards) => leaderboards_to_response(
+            &bot.games(),
+            command.user.avatar_url().unwrap_or_default(),
+            leaderboards,
+            player_name,
+            wants_image(option),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                            &command.user.name,
+                            &command.user.avatar_url().unwrap_or_default(),
+                            String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                            Colour::RED)
+        }
+    }
+}
+
+/// The name given in the `name` option, where `me` or leaving it out stands for the caller's linked player
+async fn resolve_player<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+    name: &str,
+) -> Result<String, CreateInteractionResponseData<'a>> {
+    match find_option(option, name) {
+        Some(CommandDataOptionValue::String(player_name))
+            if !player_name.eq_ignore_ascii_case("me") =>
         {
-            Ok(leaderboards) => leaderboards_to_response(
-                command.user.avatar_url().unwrap_or_default().to_owned(),
-                leaderboards,
-                player_name.to_owned(),
-            ),
-            Err(err) => {
-                println!("{}", err.to_string());
-                default_embed_from_content(
-                                &command.user.name,
-                                &command.user.avatar_url().unwrap_or_default(),
-                                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
-                                Colour::RED)
-            }
+            return Ok(player_name.to_owned())
         }
-    } else {
-        default_embed_from_content(
+        Some(CommandDataOptionValue::String(_)) | None => {}
+        Some(_) => {
+            return Err(default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("Not a valid sub command. What happened here?"),
+                Colour::RED,
+            ))
+        }
+    }
+
+    match bot
+        .store
+        .linked_player(*command.user.id.as_u64() as i64)
+        .await
+    {
+        Ok(Some(player_name)) => Ok(player_name),
+        Ok(None) => Err(default_embed_from_content(
             &command.user.name,
             &command.user.avatar_url().unwrap_or_default(),
-            String::from("Not a valid sub command. What happened here?"),
+            String::from(
+                "You haven't linked a player yet, use `/leaderboard link` or give a player name.",
+            ),
             Colour::RED,
-        )
+        )),
+        Err(err) => {
+            println!("{}", err);
+            Err(default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED))
+        }
     }
 }
 
+/// Explains whether a player dropped off or never appeared, and suggests names they may have meant
+async fn missing_player_description(bot: &Bot, player_name: &str) -> String {
+    let mut description = match bot.store.player_last_seen(player_name).await {
+        Ok(last_seen) if !last_seen.is_empty() => {
+            let mut s = format!(
+                "**{}** currently isn't on any leaderboard, but dropped off:",
+                player_name.replace('_', "\\_")
+            );
+            for (game, submission_id) in last_seen {
+                s += &format!("\n- {} (last seen in submission {})", game, submission_id);
+            }
+            s
+        }
+        Ok(_) => format!(
+            "**{}** has never been on a leaderboard.",
+            player_name.replace('_', "\\_")
+        ),
+        Err(err) => {
+            println!("{}", err);
+            format!(
+                "**{}** currently isn't on any leaderboard.",
+                player_name.replace('_', "\\_")
+            )
+        }
+    };
+
+    match bot.store.all_player_names().await {
+        Ok(names) => {
+            let suggestions: Vec<String> = closest(player_name, &names, 4)
+                .into_iter()
+                .filter(|name| *name != player_name)
+                .take(3)
+                .map(|name| format!("**{}**", name.replace('_', "\\_")))
+                .collect();
+            if !suggestions.is_empty() {
+                description += &format!("\n\nDid you mean {}?", suggestions.join(", "));
+            }
+        }
+        Err(err) => println!("{}", err),
+    }
+    description
+}
+
 fn leaderboards_to_response(
+    games: &GameRegistry,
     avatar_url: String,
     leaderboards: Vec<LeaderboardRow>,
     player_name: String,
+    as_image: bool,
 ) -> CreateInteractionResponseData<'static> {
     let mut embed = CreateEmbed::default();
     embed.footer(|f| {
         f.text(format!(
             "Submission ID: {}",
             leaderboards
-                .get(0)
-                .and_then(|row| Some(row.unix_time_stamp.to_string()))
+                .first()
+                .map(|row| row.unix_time_stamp.to_string())
                 .unwrap_or_else(|| String::from("unknown"))
         ))
         .icon_url(avatar_url)
     });
     embed.timestamp(Utc::now().to_rfc3339());
 
-    if leaderboards.len() == 0 {
+    let mut message = CreateInteractionResponseData::default();
+
+    if leaderboards.is_empty() {
         embed.colour(Colour::RED);
-        embed.description(String::from(format!(
+        embed.description(format!(
             "**{}** currently isn't on any leaderboard.",
             player_name
-        )));
+        ));
+    } else if as_image {
+        embed.colour(Colour::from_rgb(106, 86, 246));
+
+        let rows: Vec<ImageRow> = leaderboards
+            .iter()
+            .map(|row| ImageRow {
+                position: row.position,
+                name: &row.game,
+                score: row.score,
+                score_unit: games.score_unit(&row.game),
+            })
+            .collect();
+        attach_image(
+            &mut embed,
+            &mut message,
+            &format!("{}'s leaderboards", player_name),
+            &rows,
+            leaderboards[0].unix_time_stamp,
+        );
     } else {
         embed.colour(Colour::from_rgb(106, 86, 246));
-        let mut s = String::from(format!(
+        let mut s = format!(
             "**{}** leaderboards ({}):",
             player_name,
             &leaderboards.len()
-        ));
+        );
         for row in leaderboards {
-            s += &row.get_leaderboard_string();
+            s += &row.get_leaderboard_string(games.score_unit(&row.game));
         }
         embed.description(s);
     }
 
+    message.add_embed(embed);
+    message
+}
+
+fn attach_image(
+    embed: &mut CreateEmbed,
+    message: &mut CreateInteractionResponseData,
+    title: &str,
+    rows: &[ImageRow],
+    submission_id: i64,
+) {
+    match render_leaderboard(title, rows, submission_id) {
+        Ok(png) => {
+            embed.image(format!("attachment://{}", IMAGE_NAME));
+            message.add_file(AttachmentType::Bytes {
+                data: Cow::Owned(png),
+                filename: String::from(IMAGE_NAME),
+            });
+        }
+        Err(err) => {
+            println!("{}", err);
+            embed.colour(Colour::RED);
+            embed.description(
+                "An error occurred trying to draw the leaderboard. Contact Fesa if this persists",
+            );
+        }
+    }
+}
+
+fn wants_image(option: &[CommandDataOption]) -> bool {
+    matches!(
+        find_option(option, "format"),
+        Some(CommandDataOptionValue::String(format)) if format == "image"
+    )
+}
+
+fn export_format_option(
+    option: &mut CreateApplicationCommandOption,
+) -> &mut CreateApplicationCommandOption {
+    option
+        .name("format")
+        .description("The file format")
+        .kind(CommandOptionType::String)
+        .add_string_choice("CSV", "csv")
+        .add_string_choice("JSON", "json")
+        .required(true)
+}
+
+async fn export_game_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(CommandDataOptionValue::String(game)) = find_option(option, "game") else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+    let Some(game) = bot.games().by_code(game).cloned() else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("That game doesn't exist (anymore)."),
+            Colour::RED,
+        );
+    };
+
+    let rows = match find_option(option, "submission") {
+        Some(CommandDataOptionValue::Integer(id)) => {
+            bot.store.submission_leaderboard(&game.name, *id).await
+        }
+        _ => bot.store.latest_game_leaderboard(&game.name).await,
+    };
+    match rows {
+        Ok(rows) if rows.is_empty() => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!("Couldn't find that submission of **{}**.", game.name),
+            Colour::RED,
+        ),
+        Ok(rows) => export_to_response(
+            command,
+            &format!("{}-{}", game.code, rows[0].unix_time_stamp),
+            format!(
+                "**{}** in submission {}",
+                game.name, rows[0].unix_time_stamp
+            ),
+            rows,
+            export_format(option),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+async fn export_player_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let player_name = match resolve_player(bot, command, option, "player").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+
+    match bot.store.latest_player_leaderboards(&player_name).await {
+        Ok(rows) if rows.is_empty() => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            missing_player_description(bot, &player_name).await,
+            Colour::RED,
+        ),
+        Ok(rows) => export_to_response(
+            command,
+            &player_name,
+            format!("**{}**'s leaderboards", player_name.replace('_', "\\_")),
+            rows,
+            export_format(option),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+fn export_format(option: &[CommandDataOption]) -> ExportFormat {
+    ExportFormat::from_option(match find_option(option, "format") {
+        Some(CommandDataOptionValue::String(format)) => Some(format.as_str()),
+        _ => None,
+    })
+}
+
+fn export_to_response<'a>(
+    command: &'a ApplicationCommandInteraction,
+    file_name: &str,
+    what: String,
+    rows: Vec<LeaderboardRow>,
+    format: ExportFormat,
+) -> CreateInteractionResponseData<'a> {
+    let data = match format.encode(&rows) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("{}", err);
+            return default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to export the leaderboards. Contact Fesa if this persists"),
+                Colour::RED);
+        }
+    };
+
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Rows: {}", rows.len()))
+            .icon_url(command.user.avatar_url().unwrap_or_default())
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+    embed.description(format!("Exported {}.", what));
+
+    let mut message = CreateInteractionResponseData::default();
+    message.add_file(AttachmentType::Bytes {
+        data: Cow::Owned(data),
+        filename: format!("{}.{}", file_name, format.extension()),
+    });
+    message.add_embed(embed);
+    message
+}
+
+async fn history_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(CommandDataOptionValue::String(game)) = find_option(option, "game") else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+    let player_name = match resolve_player(bot, command, option, "player").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+
+    let Some(game) = bot.games().by_code(game).cloned() else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("That game doesn't exist (anymore)."),
+            Colour::RED,
+        );
+    };
+
+    match bot.store.player_history(&game.name, &player_name).await {
+        Ok(history) => history_to_response(bot, &command.user, history, &game, &player_name),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+fn history_to_response(
+    bot: &Bot,
+    user: &User,
+    history: Vec<HistoryRow>,
+    game: &Game,
+    player_name: &str,
+) -> CreateInteractionResponseData<'static> {
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Submissions: {}", history.len()))
+            .icon_url(user.avatar_url().unwrap_or_default())
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+
+    let mut message = CreateInteractionResponseData::default();
+
+    if history.iter().all(|row| row.position.is_none()) {
+        embed.colour(Colour::RED);
+        embed.description(format!(
+            "**{}** has never been on the {} leaderboard.",
+            player_name.replace('_', "\\_"),
+            game.name
+        ));
+    } else {
+        embed.colour(Colour::from_rgb(106, 86, 246));
+
+        let pages: Vec<String> = history
+            .chunks(10)
+            .map(|chunk| {
+                let mut s = format!(
+                    "**{}** on {} per submission, newest first:",
+                    player_name.replace('_', "\\_"),
+                    game.name
+                );
+                for row in chunk {
+                    s += &row.get_history_string(&game.score_unit);
+                }
+                s
+            })
+            .collect();
+        paginate(
+            bot,
+            *user.id.as_u64(),
+            &mut message,
+            description_pages(&embed, pages),
+        );
+        return message;
+    }
+    message.add_embed(embed);
+    message
+}
+
+async fn diff_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(CommandDataOptionValue::String(game)) = find_option(option, "game") else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+    let submission_option = |name: &str| match find_option(option, name) {
+        Some(CommandDataOptionValue::Integer(id)) => Some(*id),
+        _ => None,
+    };
+    let from = submission_option("from");
+    let to = submission_option("to");
+
+    let Some(game) = bot.games().by_code(game).cloned() else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("That game doesn't exist (anymore)."),
+            Colour::RED,
+        );
+    };
+
+    match fetch_diff(bot, &game, from, to).await {
+        Ok(Ok(diff)) => diff_to_response(bot, &command.user, diff, &game),
+        Ok(Err(content)) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            content,
+            Colour::RED,
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+/// The inner error is the message for the user when there aren't two submissions to compare
+async fn fetch_diff(
+    bot: &Bot,
+    game: &Game,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Result<LeaderboardDiff, String>, sqlx::Error> {
+    let submissions = bot.store.game_submissions(&game.name).await?;
+    let not_found = || {
+        Ok(Err(format!(
+            "Couldn't find two submissions of **{}** to compare.",
+            game.name
+        )))
+    };
+
+    let to = match to {
+        Some(id) => submissions.iter().find(|s| s.unix_time_stamp == id),
+        None => submissions.iter().find(|s| s.valid),
+    };
+    let Some(to) = to else {
+        return not_found();
+    };
+    let from = match from {
+        Some(id) => submissions.iter().find(|s| s.unix_time_stamp == id),
+        None => submissions
+            .iter()
+            .find(|s| s.valid && s.unix_time_stamp < to.unix_time_stamp),
+    };
+    let Some(from) = from else {
+        return not_found();
+    };
+    if from.unix_time_stamp == to.unix_time_stamp {
+        return Ok(Err(String::from(
+            "`from` and `to` are the same submission, pick two different ones.",
+        )));
+    }
+    if from.unix_time_stamp > to.unix_time_stamp {
+        return Ok(Err(format!(
+            "Submission `{}` is newer than `{}`, `from` has to be the older one.",
+            from.unix_time_stamp, to.unix_time_stamp
+        )));
+    }
+
+    let from_board = bot
+        .store
+        .submission_leaderboard(&game.name, from.unix_time_stamp)
+        .await?;
+    let to_board = bot
+        .store
+        .submission_leaderboard(&game.name, to.unix_time_stamp)
+        .await?;
+
+    Ok(Ok(LeaderboardDiff::between(
+        from.unix_time_stamp,
+        from_board,
+        to.unix_time_stamp,
+        to_board,
+    )))
+}
+
+fn diff_to_response(
+    bot: &Bot,
+    user: &User,
+    diff: LeaderboardDiff,
+    game: &Game,
+) -> CreateInteractionResponseData<'static> {
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Submission IDs: {} → {}", diff.from_id, diff.to_id))
+            .icon_url(user.avatar_url().unwrap_or_default())
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+
+    let mut message = CreateInteractionResponseData::default();
+    paginate(
+        bot,
+        *user.id.as_u64(),
+        &mut message,
+        description_pages(&embed, diff.pages(game)),
+    );
+    message
+}
+
+async fn compare_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let player_a = match resolve_player(bot, command, option, "player_a").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+    let player_b = match resolve_player(bot, command, option, "player_b").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+
+    let boards = match (
+        bot.store.latest_player_leaderboards(&player_a).await,
+        bot.store.latest_player_leaderboards(&player_b).await,
+    ) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(err), _) | (_, Err(err)) => {
+            println!("{}", err);
+            return default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED);
+        }
+    };
+
+    match boards {
+        // Only one of them missing still leaves something to compare
+        (a, b) if a.is_empty() && b.is_empty() => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!(
+                "{}\n\n{}",
+                missing_player_description(bot, &player_a).await,
+                missing_player_description(bot, &player_b).await
+            ),
+            Colour::RED,
+        ),
+        (a, b) => compare_to_response(
+            &bot.games(),
+            command.user.avatar_url().unwrap_or_default(),
+            (&player_a, a),
+            (&player_b, b),
+        ),
+    }
+}
+
+/// One inline field per game either player is ranked on, lower position leads. A player that's on
+/// none of them is marked as absent
+fn compare_to_response(
+    games: &GameRegistry,
+    avatar_url: String,
+    (player_a, boards_a): (&str, Vec<LeaderboardRow>),
+    (player_b, boards_b): (&str, Vec<LeaderboardRow>),
+) -> CreateInteractionResponseData<'static> {
+    let mut game_names: Vec<&str> = boards_a
+        .iter()
+        .chain(boards_b.iter())
+        .map(|row| row.game.as_str())
+        .collect();
+    game_names.sort();
+    game_names.dedup();
+
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| {
+        f.text(format!("Games: {}", game_names.len()))
+            .icon_url(avatar_url)
+    });
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+
+    let escaped_a = player_a.replace('_', "\\_");
+    let escaped_b = player_b.replace('_', "\\_");
+    let (mut wins_a, mut wins_b) = (0, 0);
+
+    // Embeds can't hold more than 25 fields
+    for game in game_names.into_iter().take(25) {
+        let unit = games.score_unit(game);
+        let row_a = boards_a.iter().find(|row| row.game == game);
+        let row_b = boards_b.iter().find(|row| row.game == game);
+        let line = |row: Option<&LeaderboardRow>| match row {
+            Some(row) => format!("[{}]: {} {}", row.position, row.score, unit),
+            None => String::from("~~not ranked~~"),
+        };
+
+        let leader = match (row_a, row_b) {
+            (Some(a), Some(b)) if a.position < b.position => Some(&escaped_a),
+            (Some(a), Some(b)) if b.position < a.position => Some(&escaped_b),
+            (Some(_), None) => Some(&escaped_a),
+            (None, Some(_)) => Some(&escaped_b),
+            _ => None,
+        };
+        // Both rows come from the latest submission of the game
+        let submission_id = row_a.or(row_b).map(|row| row.unix_time_stamp);
+        if leader == Some(&escaped_a) {
+            wins_a += 1;
+        } else if leader == Some(&escaped_b) {
+            wins_b += 1;
+        }
+
+        embed.field(
+            game,
+            format!(
+                "{}: {}\n{}: {}\n{}\nSubmission ID: {}",
+                escaped_a,
+                line(row_a),
+                escaped_b,
+                line(row_b),
+                leader
+                    .map(|name| format!("**{}** leads", name))
+                    .unwrap_or_else(|| String::from("Tied")),
+                submission_id.unwrap_or_default()
+            ),
+            true,
+        );
+    }
+
+    let absent = |boards: &[LeaderboardRow]| {
+        if boards.is_empty() {
+            " (not on any leaderboard)"
+        } else {
+            ""
+        }
+    };
+    embed.description(format!(
+        "**{}**{} vs **{}**{}, {} to {}",
+        escaped_a,
+        absent(&boards_a),
+        escaped_b,
+        absent(&boards_b),
+        wins_a,
+        wins_b
+    ));
+
     let mut message = CreateInteractionResponseData::default();
     message.add_embed(embed);
     message
 }
+
+async fn follow_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let player_name = match resolve_player(bot, command, option, "player").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+    let channel_id = match find_option(option, "deliver") {
+        Some(CommandDataOptionValue::String(deliver)) if deliver == "here" => {
+            Some(*command.channel_id.as_u64() as i64)
+        }
+        _ => None,
+    };
+
+    match bot
+        .store
+        .insert_follow(*command.user.id.as_u64() as i64, &player_name, channel_id)
+        .await
+    {
+        Ok(_) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!(
+                "You'll be notified {} when **{}** moves on a leaderboard.",
+                if channel_id.is_some() {
+                    "in this channel"
+                } else {
+                    "in your DMs"
+                },
+                player_name.replace('_', "\\_")
+            ),
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from(
+                    "An error occurred trying to save the follow. Contact Fesa if this persists",
+                ),
+                Colour::RED,
+            )
+        }
+    }
+}
+
+async fn unfollow_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let player_name = match resolve_player(bot, command, option, "player").await {
+        Ok(player_name) => player_name,
+        Err(response) => return response,
+    };
+
+    match bot
+        .store
+        .delete_follow(*command.user.id.as_u64() as i64, &player_name)
+        .await
+    {
+        Ok(0) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!(
+                "You aren't following **{}**.",
+                player_name.replace('_', "\\_")
+            ),
+            Colour::RED,
+        ),
+        Ok(_) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!(
+                "You stopped following **{}**.",
+                player_name.replace('_', "\\_")
+            ),
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from(
+                    "An error occurred trying to remove the follow. Contact Fesa if this persists",
+                ),
+                Colour::RED,
+            )
+        }
+    }
+}
+
+async fn following_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+) -> CreateInteractionResponseData<'a> {
+    match bot
+        .store
+        .user_follows(*command.user.id.as_u64() as i64)
+        .await
+    {
+        Ok(follows) if follows.is_empty() => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("You aren't following anyone, use `/leaderboard follow` to start."),
+            Colour::RED,
+        ),
+        Ok(follows) => {
+            let mut embed = CreateEmbed::default();
+            embed.footer(|f| {
+                f.text(format!("Following: {}", follows.len()))
+                    .icon_url(command.user.avatar_url().unwrap_or_default())
+            });
+            embed.timestamp(Utc::now().to_rfc3339());
+            embed.colour(Colour::from_rgb(106, 86, 246));
+
+            let pages: Vec<String> = follows
+                .chunks(10)
+                .map(|chunk| {
+                    let mut s = String::from("Players you're following:");
+                    for follow in chunk {
+                        s += &follow.get_follow_string();
+                    }
+                    s
+                })
+                .collect();
+
+            let mut message = CreateInteractionResponseData::default();
+            paginate(
+                bot,
+                *command.user.id.as_u64(),
+                &mut message,
+                description_pages(&embed, pages),
+            );
+            message
+        }
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to fetch the leaderboards. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+async fn link_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(CommandDataOptionValue::String(player_name)) = find_option(option, "name") else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+    if player_name.eq_ignore_ascii_case("me") {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("`me` is reserved, it stands for your linked player."),
+            Colour::RED,
+        );
+    }
+
+    match bot
+        .store
+        .link_account(*command.user.id.as_u64() as i64, player_name)
+        .await
+    {
+        Ok(_) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            format!(
+                "Linked you to **{}**, leave out the player or use `me` to look yourself up.",
+                player_name.replace('_', "\\_")
+            ),
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from(
+                    "An error occurred trying to save the link. Contact Fesa if this persists",
+                ),
+                Colour::RED,
+            )
+        }
+    }
+}
+
+async fn unlink_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+) -> CreateInteractionResponseData<'a> {
+    match bot
+        .store
+        .unlink_account(*command.user.id.as_u64() as i64)
+        .await
+    {
+        Ok(0) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("You haven't linked a player."),
+            Colour::RED,
+        ),
+        Ok(_) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Removed the link to your player."),
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from(
+                    "An error occurred trying to remove the link. Contact Fesa if this persists",
+                ),
+                Colour::RED,
+            )
+        }
+    }
+}
+
+async fn announce_command<'a>(
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let Some(guild_id) = command.guild_id else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Announcements can only be set up in a server."),
+            Colour::RED,
+        );
+    };
+    let allowed = bot.config.owner_ids.contains(command.user.id.as_u64())
+        || command
+            .member
+            .as_ref()
+            .and_then(|member| member.permissions)
+            .is_some_and(|permissions| permissions.manage_guild());
+    if !allowed {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("You need the Manage Server permission to set up announcements."),
+            Colour::RED,
+        );
+    }
+
+    let Some(CommandDataOptionValue::String(game)) = find_option(option, "game") else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+    let Some(game) = bot.games().by_code(game).cloned() else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("That game doesn't exist (anymore)."),
+            Colour::RED,
+        );
+    };
+
+    let guild_id = *guild_id.as_u64() as i64;
+    let result = match find_option(option, "channel") {
+        Some(CommandDataOptionValue::Channel(channel)) => bot
+            .store
+            .set_announcement_channel(guild_id, &game.name, *channel.id.as_u64() as i64)
+            .await
+            .map(|_| {
+                format!(
+                    "New **{}** leaderboards will be posted in <#{}>.",
+                    game.name, channel.id
+                )
+            }),
+        _ => bot
+            .store
+            .delete_announcement_channel(guild_id, &game.name)
+            .await
+            .map(|_| {
+                format!(
+                    "New **{}** leaderboards won't be posted anymore.",
+                    game.name
+                )
+            }),
+    };
+
+    match result {
+        Ok(content) => default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            content,
+            Colour::from_rgb(106, 86, 246),
+        ),
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to save the announcement channel. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+async fn submit_command<'a>(
+    ctx: &Context,
+    bot: &Bot,
+    command: &'a ApplicationCommandInteraction,
+    option: &'a [CommandDataOption],
+) -> CreateInteractionResponseData<'a> {
+    let trusted = bot.config.owner_ids.contains(command.user.id.as_u64())
+        || command.member.as_ref().is_some_and(|member| {
+            member
+                .roles
+                .iter()
+                .any(|role| bot.config.trusted_roles.contains(role.as_u64()))
+        });
+    if !trusted {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("You're not allowed to submit leaderboards."),
+            Colour::RED,
+        );
+    }
+
+    let (
+        Some(CommandDataOptionValue::String(game)),
+        Some(CommandDataOptionValue::Attachment(attachment)),
+    ) = (find_option(option, "game"), find_option(option, "file"))
+    else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("Not a valid sub command. What happened here?"),
+            Colour::RED,
+        );
+    };
+
+    let content = match attachment.download().await {
+        Ok(content) => content,
+        Err(err) => {
+            println!("{}", err);
+            return default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("Couldn't download the attached file, try again later."),
+                Colour::RED,
+            );
+        }
+    };
+
+    let Some(game) = bot.games().by_code(game).cloned() else {
+        return default_embed_from_content(
+            &command.user.name,
+            &command.user.avatar_url().unwrap_or_default(),
+            String::from("That game doesn't exist (anymore)."),
+            Colour::RED,
+        );
+    };
+
+    let rows = match parse_submission(&attachment.filename, &content, game.descending) {
+        Ok(rows) => rows,
+        Err(reason) => {
+            return default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                reason,
+                Colour::RED,
+            )
+        }
+    };
+
+    // Another submission in the same second moves the ID along, so it's unique either way
+    match bot
+        .store
+        .insert_submission(
+            &game.name,
+            Utc::now().timestamp(),
+            *command.user.id.as_u64(),
+            &rows,
+        )
+        .await
+    {
+        Ok(submission_id) => {
+            audit::record(
+                ctx,
+                bot,
+                *command.user.id.as_u64(),
+                "submit",
+                &format!("{} ({})", submission_id, game.name),
+            );
+
+            let mut embed = CreateEmbed::default();
+            embed.footer(|f| {
+                f.text(format!("Submission ID: {}", submission_id))
+                    .icon_url(command.user.avatar_url().unwrap_or_default())
+            });
+            embed.timestamp(Utc::now().to_rfc3339());
+            embed.colour(Colour::from_rgb(106, 86, 246));
+            embed.description(format!(
+                "Submitted {} players to **{}**.",
+                rows.len(),
+                game.name
+            ));
+
+            let mut message = CreateInteractionResponseData::default();
+            message.add_embed(embed);
+            message
+        }
+        Err(err) => {
+            println!("{}", err);
+            default_embed_from_content(
+                &command.user.name,
+                &command.user.avatar_url().unwrap_or_default(),
+                String::from("An error occurred trying to save the submission. Contact Fesa if this persists"),
+                Colour::RED)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
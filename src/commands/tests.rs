@@ -0,0 +1,736 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::{json, Value};
+use serenity::{
+    builder::CreateInteractionResponseData,
+    model::prelude::interaction::application_command::ApplicationCommandInteraction,
+};
+use sqlx::postgres::PgPoolOptions;
+
+use super::*;
+use crate::{
+    config::{Config, PoolConfig},
+    store::{board, row, LeaderboardStore, MemoryStore},
+};
+
+const TEW: &str = "Team EggWars";
+const FFA: &str = "Free For All";
+
+fn games() -> GameRegistry {
+    GameRegistry::from(vec![
+        Game {
+            code: String::from("tew"),
+            name: String::from(TEW),
+            score_unit: String::from("wins"),
+            descending: true,
+            active: true,
+        },
+        Game {
+            code: String::from("ffa"),
+            name: String::from(FFA),
+            score_unit: String::from("kills"),
+            descending: true,
+            active: true,
+        },
+    ])
+}
+
+/// A bot backed by the in-memory store, the pool is never connected to
+fn bot(store: MemoryStore) -> Bot {
+    Bot {
+        db: PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap(),
+        config: Config {
+            token: String::from("token"),
+            database_url: String::from("postgres://localhost/unused"),
+            owner_ids: vec![1],
+            home_guild_id: 1,
+            trusted_roles: vec![],
+            api_address: None,
+            audit_channel_id: None,
+            database: PoolConfig::default(),
+        },
+        running_paginator: Arc::new(Mutex::new(HashMap::new())),
+        games: Arc::new(Mutex::new(games())),
+        store: Arc::new(store),
+    }
+}
+
+/// `/leaderboard <subcommand>` with the given options, as Discord would send it
+fn command(subcommand: &str, options: Value) -> ApplicationCommandInteraction {
+    serde_json::from_value(json!({
+        "id": "10",
+        "application_id": "11",
+        "type": 2,
+        "data": {
+            "id": "12",
+            "name": "leaderboard",
+            "type": 1,
+            "options": [{ "name": subcommand, "type": 1, "options": options }],
+        },
+        "channel_id": "13",
+        "user": { "id": "14", "username": "tester", "discriminator": "0001", "avatar": null },
+        "token": "token",
+        "version": 1,
+        "locale": "en-US",
+    }))
+    .unwrap()
+}
+
+fn options(command: &ApplicationCommandInteraction) -> &[CommandDataOption] {
+    &command.data.options[0].options
+}
+
+fn embed(message: &CreateInteractionResponseData) -> Value {
+    message.0["embeds"][0].clone()
+}
+
+fn description(message: &CreateInteractionResponseData) -> String {
+    embed(message)["description"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn footer(message: &CreateInteractionResponseData) -> String {
+    embed(message)["footer"]["text"]
+        .as_str()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+#[tokio::test]
+async fn game_leaderboard_shows_the_first_page_of_the_latest_submission() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, board(200, 0)),
+        (TEW, 200, board(200, 5)),
+    ])
+    .await);
+    let command = command(
+        "game",
+        json!([{ "name": "game", "type": 3, "value": "tew" }]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    let description = description(&message);
+    assert!(description.starts_with("Players on Team EggWars between 1 and 10:"));
+    assert!(description.contains("\n- player1 [1]: 1004 wins"));
+    assert!(description.contains("\n- player10 [10]: 995 wins"));
+    assert!(!description.contains("player11 "));
+    assert_eq!(footer(&message), "Submission ID: 200 • Page 1/20");
+
+    let controls = &message.0["components"][0]["components"];
+    assert_eq!(controls.as_array().map(Vec::len), Some(5));
+    assert_eq!(controls[0]["disabled"], true);
+    assert_eq!(controls[1]["disabled"], true);
+    assert_eq!(controls[3]["disabled"], false);
+
+    let paginators = bot.running_paginator.lock().unwrap();
+    let session = paginators.values().next().expect("a paginator session");
+    assert_eq!(session.owner, 14);
+    assert_eq!(session.pages.len(), 20);
+    assert!(session.pages[19]["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Players on Team EggWars between 191 and 200:"));
+    assert_eq!(
+        session.pages[19]["footer"]["text"],
+        "Submission ID: 200 • Page 20/20"
+    );
+}
+
+#[tokio::test]
+async fn game_leaderboard_skips_invalidated_submissions() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, board(200, 0)),
+        (TEW, 200, board(200, 5)),
+    ])
+    .await);
+    assert_eq!(
+        bot.store.set_submission_validity(200, false).await.unwrap(),
+        1
+    );
+    let command = command(
+        "game",
+        json!([{ "name": "game", "type": 3, "value": "tew" }]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert!(description(&message).contains("\n- player1 [1]: 999 wins"));
+    assert_eq!(footer(&message), "Submission ID: 100 • Page 1/20");
+}
+
+#[tokio::test]
+async fn game_leaderboard_of_an_unknown_game_is_an_error() {
+    let bot = bot(MemoryStore::default());
+    let command = command(
+        "game",
+        json!([{ "name": "game", "type": 3, "value": "nope" }]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(description(&message), "That game doesn't exist (anymore).");
+}
+
+#[tokio::test]
+async fn game_leaderboard_opens_at_the_page_of_a_player() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(200, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "player", "type": 3, "value": "PLAYER57" },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    let description = description(&message);
+    assert!(description.starts_with("Players on Team EggWars between 51 and 60:"));
+    assert!(description.contains("\n- **player57 [57]: 943 wins**"));
+    assert!(description.contains("\n- player58 [58]: 942 wins"));
+    assert_eq!(footer(&message), "Submission ID: 100 • Page 6/20");
+    assert_eq!(
+        message.0["components"][1]["components"][0]["label"],
+        "Find player"
+    );
+}
+
+#[tokio::test]
+async fn game_leaderboard_of_a_missing_player_is_an_error() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(200, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "player", "type": 3, "value": "Some_One" },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Some\\_One** isn't on the Team EggWars leaderboard."
+    );
+}
+
+#[tokio::test]
+async fn game_leaderboard_shorter_than_a_page() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(7, 0))]).await);
+    let command = command(
+        "game",
+        json!([{ "name": "game", "type": 3, "value": "tew" }]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    let description = description(&message);
+    assert!(description.starts_with("Players on Team EggWars between 1 and 7:"));
+    assert!(description.contains("\n- player7 [7]: 993 wins"));
+    assert_eq!(footer(&message), "Submission ID: 100");
+    assert!(bot.running_paginator.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn game_leaderboard_with_fewer_rows_than_the_range() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(25, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 11 },
+            { "name": "to", "type": 4, "value": 200 },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert!(description(&message).starts_with("Players on Team EggWars between 11 and 20:"));
+    assert_eq!(footer(&message), "Submission ID: 100 • Page 1/2");
+    let paginators = bot.running_paginator.lock().unwrap();
+    let session = paginators.values().next().expect("a paginator session");
+    assert!(session.pages[1]["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Players on Team EggWars between 21 and 25:"));
+}
+
+#[tokio::test]
+async fn game_leaderboard_past_the_end_is_empty() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(25, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 30 },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Team EggWars** currently doesn't have any players on it from place 30 on."
+    );
+}
+
+#[tokio::test]
+async fn game_leaderboard_longer_than_200_with_a_page_size() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(300, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 250 },
+            { "name": "page_size", "type": 4, "value": 20 },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    let description = description(&message);
+    assert!(description.starts_with("Players on Team EggWars between 250 and 269:"));
+    assert!(description.contains("\n- player269 [269]: 731 wins"));
+    assert_eq!(footer(&message), "Submission ID: 100 • Page 1/3");
+    let paginators = bot.running_paginator.lock().unwrap();
+    let session = paginators.values().next().expect("a paginator session");
+    assert!(session.pages[2]["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Players on Team EggWars between 290 and 300:"));
+}
+
+#[tokio::test]
+async fn game_leaderboard_range_must_be_in_order() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, board(25, 0))]).await);
+    let command = command(
+        "game",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 20 },
+            { "name": "to", "type": 4, "value": 10 },
+        ]),
+    );
+
+    let message = leaderboards_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "`to` can't be a place before `from`."
+    );
+}
+
+#[tokio::test]
+async fn player_shows_every_game_with_its_score_unit() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 3, 50), row("Other", 1, 80)]),
+        (FFA, 101, vec![row("Fesa", 1, 400)]),
+    ])
+    .await);
+    let command = command(
+        "all",
+        json!([{ "name": "player", "type": 3, "value": "Fesa" }]),
+    );
+
+    let message = player_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Fesa** leaderboards (2):\n- Free For All [1]: 400 kills\n- Team EggWars [3]: 50 wins"
+    );
+}
+
+#[tokio::test]
+async fn missing_player_suggests_similar_names() {
+    let bot = bot(MemoryStore::with_submissions(vec![(
+        TEW,
+        100,
+        vec![row("Notch_", 1, 10), row("jeb", 2, 5)],
+    )])
+    .await);
+    let command = command(
+        "all",
+        json!([{ "name": "player", "type": 3, "value": "Notch" }]),
+    );
+
+    let message = player_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Notch** has never been on a leaderboard.\n\nDid you mean **Notch\\_**?"
+    );
+}
+
+#[tokio::test]
+async fn missing_player_suggests_names_from_older_submissions() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Dinnerbone", 1, 10)]),
+        (TEW, 200, vec![row("jeb", 1, 20)]),
+    ])
+    .await);
+    let command = command(
+        "all",
+        json!([{ "name": "player", "type": 3, "value": "Dinerbone" }]),
+    );
+
+    let message = player_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Dinerbone** has never been on a leaderboard.\n\nDid you mean **Dinnerbone**?"
+    );
+}
+
+#[tokio::test]
+async fn missing_player_mentions_where_they_dropped_off() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 1, 10)]),
+        (TEW, 200, vec![row("Other", 1, 20)]),
+    ])
+    .await);
+    let command = command(
+        "all",
+        json!([{ "name": "player", "type": 3, "value": "Fesa" }]),
+    );
+
+    let message = player_command(&bot, &command, options(&command)).await;
+
+    assert!(description(&message).starts_with(
+        "**Fesa** currently isn't on any leaderboard, but dropped off:\n- Team EggWars (last seen in submission 100)"
+    ));
+}
+
+#[tokio::test]
+async fn history_lists_every_valid_submission_newest_first() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 4, 10)]),
+        (TEW, 200, vec![row("Other", 1, 20)]),
+        (TEW, 300, vec![row("Fesa", 2, 30)]),
+    ])
+    .await);
+    let command = command(
+        "history",
+        json!([
+            { "name": "player", "type": 3, "value": "Fesa" },
+            { "name": "game", "type": 3, "value": "tew" },
+        ]),
+    );
+
+    let message = history_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Fesa** on Team EggWars per submission, newest first:\n- 300 [2]: 30 wins\n- 200 ~~not on the leaderboard~~\n- 100 [4]: 10 wins"
+    );
+    assert_eq!(footer(&message), "Submissions: 3");
+}
+
+#[tokio::test]
+async fn history_escapes_a_player_that_was_never_on_the_leaderboard() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, vec![row("Fesa", 1, 10)])]).await);
+    let command = command(
+        "history",
+        json!([
+            { "name": "player", "type": 3, "value": "a_b" },
+            { "name": "game", "type": 3, "value": "tew" },
+        ]),
+    );
+
+    let message = history_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**a\\_b** has never been on the Team EggWars leaderboard."
+    );
+}
+
+#[tokio::test]
+async fn diff_defaults_to_the_two_latest_valid_submissions() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 1, 10), row("Gone", 2, 5)]),
+        (TEW, 200, vec![row("New", 1, 30), row("Fesa", 2, 12)]),
+        (TEW, 300, vec![row("Ignored", 1, 99)]),
+    ])
+    .await);
+    bot.store.set_submission_validity(300, false).await.unwrap();
+    let command = command(
+        "diff",
+        json!([{ "name": "game", "type": 3, "value": "tew" }]),
+    );
+
+    let message = diff_command(&bot, &command, options(&command)).await;
+
+    let description = description(&message);
+    assert!(description.starts_with(
+        "Changes on Team EggWars from 100 to 200:\n1 new entries, 1 dropouts, 0 climbers, 1 fallers"
+    ));
+    assert!(description.contains("\n- Fesa [1 → 2] ▼1: +2 wins"));
+    assert_eq!(footer(&message), "Submission IDs: 100 → 200 • Page 1/4");
+}
+
+#[tokio::test]
+async fn diff_of_a_submission_with_itself_is_an_error() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, vec![row("Fesa", 1, 10)])]).await);
+    let command = command(
+        "diff",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 100 },
+            { "name": "to", "type": 4, "value": 100 },
+        ]),
+    );
+
+    let message = diff_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "`from` and `to` are the same submission, pick two different ones."
+    );
+}
+
+#[tokio::test]
+async fn diff_from_a_newer_submission_is_an_error() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 1, 10)]),
+        (TEW, 200, vec![row("Fesa", 1, 12)]),
+    ])
+    .await);
+    let command = command(
+        "diff",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "from", "type": 4, "value": 200 },
+            { "name": "to", "type": 4, "value": 100 },
+        ]),
+    );
+
+    let message = diff_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "Submission `200` is newer than `100`, `from` has to be the older one."
+    );
+}
+
+#[tokio::test]
+async fn compare_shows_who_leads_per_game() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 1, 50), row("Other", 2, 40)]),
+        (FFA, 101, vec![row("Other", 3, 400)]),
+    ])
+    .await);
+    let command = command(
+        "compare",
+        json!([
+            { "name": "player_a", "type": 3, "value": "Fesa" },
+            { "name": "player_b", "type": 3, "value": "Other" },
+        ]),
+    );
+
+    let message = compare_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(description(&message), "**Fesa** vs **Other**, 1 to 1");
+    let fields = embed(&message)["fields"].clone();
+    assert_eq!(fields[0]["name"], FFA);
+    assert_eq!(
+        fields[0]["value"],
+        "Fesa: ~~not ranked~~\nOther: [3]: 400 kills\n**Other** leads\nSubmission ID: 101"
+    );
+    assert_eq!(fields[1]["name"], TEW);
+    assert_eq!(
+        fields[1]["value"],
+        "Fesa: [1]: 50 wins\nOther: [2]: 40 wins\n**Fesa** leads\nSubmission ID: 100"
+    );
+    assert_eq!(footer(&message), "Games: 2");
+}
+
+#[tokio::test]
+async fn compare_marks_a_player_on_no_leaderboard_as_absent() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, vec![row("Fesa", 1, 50)])]).await);
+    let command = command(
+        "compare",
+        json!([
+            { "name": "player_a", "type": 3, "value": "Fesa" },
+            { "name": "player_b", "type": 3, "value": "Nobody" },
+        ]),
+    );
+
+    let message = compare_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "**Fesa** vs **Nobody** (not on any leaderboard), 1 to 0"
+    );
+    let fields = embed(&message)["fields"].clone();
+    assert_eq!(fields[0]["name"], TEW);
+    assert_eq!(
+        fields[0]["value"],
+        "Fesa: [1]: 50 wins\nNobody: ~~not ranked~~\n**Fesa** leads\nSubmission ID: 100"
+    );
+}
+
+#[tokio::test]
+async fn linked_player_answers_for_me() {
+    let bot = bot(MemoryStore::with_submissions(vec![(TEW, 100, vec![row("Fesa", 1, 50)])]).await);
+    let link = command(
+        "link",
+        json!([{ "name": "name", "type": 3, "value": "Fesa" }]),
+    );
+    link_command(&bot, &link, options(&link)).await;
+    let command = command(
+        "all",
+        json!([{ "name": "player", "type": 3, "value": "me" }]),
+    );
+
+    let message = player_command(&bot, &command, options(&command)).await;
+
+    assert!(description(&message).starts_with("**Fesa** leaderboards (1):"));
+}
+
+#[tokio::test]
+async fn unfollow_ignores_the_case_of_the_name() {
+    let bot = bot(MemoryStore::default());
+    let follow = command(
+        "follow",
+        json!([{ "name": "player", "type": 3, "value": "Fesa" }]),
+    );
+    follow_command(&bot, &follow, options(&follow)).await;
+    let unfollow = command(
+        "unfollow",
+        json!([{ "name": "player", "type": 3, "value": "fesa" }]),
+    );
+
+    let message = unfollow_command(&bot, &unfollow, options(&unfollow)).await;
+
+    assert_eq!(description(&message), "You stopped following **fesa**.");
+    let following = command("following", json!([]));
+    assert_eq!(
+        description(&following_command(&bot, &following).await),
+        "You aren't following anyone, use `/leaderboard follow` to start."
+    );
+}
+
+#[tokio::test]
+async fn export_attaches_the_requested_submission() {
+    let bot = bot(MemoryStore::with_submissions(vec![
+        (TEW, 100, vec![row("Fesa", 1, 10)]),
+        (TEW, 200, vec![row("Other", 1, 20)]),
+    ])
+    .await);
+    let command = command(
+        "export",
+        json!([
+            { "name": "game", "type": 3, "value": "tew" },
+            { "name": "format", "type": 3, "value": "csv" },
+            { "name": "submission", "type": 4, "value": 100 },
+        ]),
+    );
+
+    let message = export_game_command(&bot, &command, options(&command)).await;
+
+    assert_eq!(
+        description(&message),
+        "Exported **Team EggWars** in submission 100."
+    );
+    let [AttachmentType::Bytes { data, filename }] = message.1.as_slice() else {
+        panic!("expected one attachment");
+    };
+    assert_eq!(filename, "tew-100.csv");
+    assert_eq!(
+        std::str::from_utf8(data).unwrap(),
+        "player,position,score,game,unix_time_stamp\nFesa,1,10,Team EggWars,100\n"
+    );
+}
+
+#[tokio::test]
+async fn deleted_submissions_disappear_from_the_store() {
+    let store =
+        MemoryStore::with_submissions(vec![(TEW, 100, board(3, 0)), (TEW, 200, board(3, 0))]).await;
+
+    assert_eq!(store.delete_submission(200).await.unwrap(), 1);
+    assert_eq!(store.delete_submission(200).await.unwrap(), 0);
+
+    let latest = store.latest_game_leaderboard(TEW).await.unwrap();
+    assert!(latest.iter().all(|row| row.unix_time_stamp == 100));
+    assert_eq!(store.game_submissions(TEW).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn submissions_stay_unannounced_until_marked() {
+    let store =
+        MemoryStore::with_submissions(vec![(TEW, 100, board(3, 0)), (FFA, 200, board(3, 0))]).await;
+    store.set_submission_validity(200, false).await.unwrap();
+
+    let unannounced = store.unannounced_submissions().await.unwrap();
+    assert_eq!(unannounced.len(), 1);
+    assert_eq!(unannounced[0].unix_time_stamp, 100);
+
+    store.mark_submission_announced(TEW, 100).await.unwrap();
+    assert!(store.unannounced_submissions().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn followers_are_found_regardless_of_case() {
+    let store = MemoryStore::default();
+    store.insert_follow(14, "Fesa", None).await.unwrap();
+    store.insert_follow(15, "player1", Some(3)).await.unwrap();
+
+    let follows = store
+        .followers_of(&[String::from("FESA"), String::from("player2")])
+        .await
+        .unwrap();
+    assert_eq!(follows.len(), 1);
+    assert_eq!(follows[0].discord_user_id, 14);
+}
+
+#[test]
+fn player_pages_escape_underscores() {
+    let games = games();
+    let game = games.by_code("tew").unwrap();
+    let rows = vec![LeaderboardRow {
+        player: String::from("a_b"),
+        position: 1,
+        score: 3,
+        game: String::from(TEW),
+        unix_time_stamp: 100,
+    }];
+
+    assert_eq!(
+        players_page(&rows, game, 1, 1, None),
+        "Players on Team EggWars between 1 and 1:\n- a\\_b [1]: 3 wins"
+    );
+}
+
+#[test]
+fn deferred_responses_keep_their_embeds() {
+    let response = default_embed_from_content(
+        &String::from("user"),
+        &String::new(),
+        String::from("Done"),
+        Colour::RED,
+    );
+
+    let edit = deferred_response(&response).unwrap();
+    assert_eq!(edit.0["embeds"][0]["description"], "Done");
+}
+
+#[test]
+fn deferred_responses_reject_what_an_edit_cant_carry() {
+    let mut response = default_embed_from_content(
+        &String::from("user"),
+        &String::new(),
+        String::from("Done"),
+        Colour::RED,
+    );
+    response.ephemeral(true);
+
+    assert!(deferred_response(&response).is_err());
+}
@@ -1,5 +1,5 @@
 use chrono::Utc;
-use serenity::{builder::{CreateEmbed, CreateInteractionResponseData}, utils::Colour};
+use serenity::{builder::{CreateEmbed, CreateInteractionResponseData}, model::prelude::interaction::application_command::{CommandDataOption, CommandDataOptionValue}, utils::Colour};
 
 pub fn default_embed_from_content(username: &String, avatar_url: &String, content: String, colour: Colour) -> CreateInteractionResponseData<'static> {
     let mut embed = CreateEmbed::default();
@@ -15,4 +15,11 @@ pub fn default_embed_from_content(username: &String, avatar_url: &String, conten
     let mut message = CreateInteractionResponseData::default();
     message.add_embed(embed);
     message
-}
\ No newline at end of file
+}
+
+pub fn find_option<'a>(option: &'a [CommandDataOption], name: &str) -> Option<&'a CommandDataOptionValue> {
+    option
+        .iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.resolved.as_ref())
+}
@@ -0,0 +1,139 @@
+use std::{env, fmt, fs, net::SocketAddr, time::Duration};
+
+use serde::Deserialize;
+
+const TOKEN_ENV: &str = "LEADERBOARD_BOT_TOKEN";
+const DATABASE_URL_ENV: &str = "LEADERBOARD_BOT_DATABASE_URL";
+
+pub enum ConfigError {
+    Read(String, std::io::Error),
+    Parse(toml::de::Error),
+    Missing(&'static str),
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => write!(f, "couldn't read {}: {}", path, err),
+            ConfigError::Parse(err) => write!(f, "couldn't parse the config: {}", err),
+            ConfigError::Missing(key) => write!(f, "`{}` is missing", key),
+            ConfigError::Invalid(key, reason) => write!(f, "`{}` is invalid: {}", key, reason),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawConfig {
+    token: Option<String>,
+    database_url: Option<String>,
+    owner_ids: Option<Vec<u64>>,
+    home_guild_id: Option<u64>,
+    #[serde(default)]
+    trusted_roles: Vec<u64>,
+    api_address: Option<SocketAddr>,
+    audit_channel_id: Option<u64>,
+    #[serde(default)]
+    database: PoolConfig,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub acquire_timeout_seconds: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            max_connections: 5,
+            acquire_timeout_seconds: 30,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_secs(self.acquire_timeout_seconds)
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub token: String,
+    pub database_url: String,
+    pub owner_ids: Vec<u64>,
+    pub home_guild_id: u64,
+    pub trusted_roles: Vec<u64>,
+    pub api_address: Option<SocketAddr>,
+    pub audit_channel_id: Option<u64>,
+    pub database: PoolConfig,
+}
+
+impl Config {
+    /// Reads the config file, `LEADERBOARD_BOT_TOKEN` and `LEADERBOARD_BOT_DATABASE_URL`
+    /// take precedence over the values in the file
+    pub fn load(path: &str) -> Result<Config, ConfigError> {
+        let content =
+            fs::read_to_string(path).map_err(|err| ConfigError::Read(path.to_owned(), err))?;
+        let raw = toml::from_str::<RawConfig>(&content).map_err(ConfigError::Parse)?;
+
+        let token = env::var(TOKEN_ENV)
+            .ok()
+            .or(raw.token)
+            .ok_or(ConfigError::Missing("token"))?;
+        let database_url = env::var(DATABASE_URL_ENV)
+            .ok()
+            .or(raw.database_url)
+            .ok_or(ConfigError::Missing("database_url"))?;
+
+        let config = Config {
+            token,
+            database_url,
+            owner_ids: raw.owner_ids.ok_or(ConfigError::Missing("owner_ids"))?,
+            home_guild_id: raw
+                .home_guild_id
+                .ok_or(ConfigError::Missing("home_guild_id"))?,
+            trusted_roles: raw.trusted_roles,
+            api_address: raw.api_address,
+            audit_channel_id: raw.audit_channel_id,
+            database: raw.database,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.token.trim().is_empty() {
+            return Err(ConfigError::Invalid("token", String::from("it's empty")));
+        }
+        if !self.database_url.starts_with("postgres://")
+            && !self.database_url.starts_with("postgresql://")
+        {
+            return Err(ConfigError::Invalid(
+                "database_url",
+                String::from("it should start with postgres:// or postgresql://"),
+            ));
+        }
+        if self.owner_ids.is_empty() {
+            return Err(ConfigError::Invalid(
+                "owner_ids",
+                String::from("at least one owner is needed"),
+            ));
+        }
+        if self.home_guild_id == 0 {
+            return Err(ConfigError::Invalid(
+                "home_guild_id",
+                String::from("it should be a guild ID"),
+            ));
+        }
+        if self.database.max_connections == 0 {
+            return Err(ConfigError::Invalid(
+                "database.max_connections",
+                String::from("it should be at least 1"),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::{commands::LeaderboardRow, games::Game};
+
+pub struct Movement {
+    pub player: String,
+    pub from_position: i32,
+    pub to_position: i32,
+    pub score_delta: i32,
+}
+
+impl Movement {
+    /// Positive when the player climbed
+    pub fn places(&self) -> i32 {
+        self.from_position - self.to_position
+    }
+
+    pub fn get_movement_string(&self, score_unit: &str) -> String {
+        let places = match self.places() {
+            0 => String::from("="),
+            p if p > 0 => format!("▲{}", p),
+            p => format!("▼{}", -p),
+        };
+        format!(
+            "\n- {} [{} → {}] {}: {:+} {}",
+            self.player.replace('_', "\\_"),
+            self.from_position,
+            self.to_position,
+            places,
+            self.score_delta,
+            score_unit
+        )
+    }
+}
+
+pub struct LeaderboardDiff {
+    pub from_id: i64,
+    pub to_id: i64,
+    pub new_entries: Vec<LeaderboardRow>,
+    pub dropouts: Vec<LeaderboardRow>,
+    pub movements: Vec<Movement>,
+}
+
+impl LeaderboardDiff {
+    pub fn between(
+        from_id: i64,
+        from: Vec<LeaderboardRow>,
+        to_id: i64,
+        to: Vec<LeaderboardRow>,
+    ) -> LeaderboardDiff {
+        let mut old: HashMap<String, LeaderboardRow> = from
+            .into_iter()
+            .map(|row| (row.player.clone(), row))
+            .collect();
+
+        let mut new_entries = vec![];
+        let mut movements = vec![];
+        for row in to {
+            match old.remove(&row.player) {
+                Some(previous) => movements.push(Movement {
+                    player: row.player,
+                    from_position: previous.position,
+                    to_position: row.position,
+                    score_delta: row.score - previous.score,
+                }),
+                None => new_entries.push(row),
+            }
+        }
+
+        let mut dropouts: Vec<LeaderboardRow> = old.into_values().collect();
+        dropouts.sort_by_key(|row| row.position);
+
+        LeaderboardDiff {
+            from_id,
+            to_id,
+            new_entries,
+            dropouts,
+            movements,
+        }
+    }
+
+    pub fn pages(&self, game: &Game) -> Vec<String> {
+        let mut pages = vec![];
+
+        let mut climbers: Vec<&Movement> =
+            self.movements.iter().filter(|m| m.places() > 0).collect();
+        climbers.sort_by_key(|m| -m.places());
+        let mut fallers: Vec<&Movement> =
+            self.movements.iter().filter(|m| m.places() < 0).collect();
+        fallers.sort_by_key(|m| m.places());
+
+        let mut summary = format!(
+            "Changes on {} from {} to {}:\n{} new entries, {} dropouts, {} climbers, {} fallers",
+            game.name,
+            self.from_id,
+            self.to_id,
+            self.new_entries.len(),
+            self.dropouts.len(),
+            climbers.len(),
+            fallers.len()
+        );
+        if !climbers.is_empty() {
+            summary += "\n\n**Biggest climbers:**";
+            for movement in climbers.iter().take(5) {
+                summary += &movement.get_movement_string(&game.score_unit);
+            }
+        }
+        if !fallers.is_empty() {
+            summary += "\n\n**Biggest fallers:**";
+            for movement in fallers.iter().take(5) {
+                summary += &movement.get_movement_string(&game.score_unit);
+            }
+        }
+        pages.push(summary);
+
+        for chunk in self.new_entries.chunks(10) {
+            let mut s = String::from("**New entries:**");
+            for row in chunk {
+                s += &row.get_player_string(&game.score_unit);
+            }
+            pages.push(s);
+        }
+
+        for chunk in self.dropouts.chunks(10) {
+            let mut s = String::from("**Dropped off:**");
+            for row in chunk {
+                s += &row.get_player_string(&game.score_unit);
+            }
+            pages.push(s);
+        }
+
+        for chunk in self.movements.chunks(10) {
+            let mut s = String::from("**Score changes:**");
+            for movement in chunk {
+                s += &movement.get_movement_string(&game.score_unit);
+            }
+            pages.push(s);
+        }
+
+        pages
+    }
+}
@@ -0,0 +1,53 @@
+use crate::commands::LeaderboardRow;
+
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_option(value: Option<&str>) -> ExportFormat {
+        match value {
+            Some("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn encode(&self, rows: &[LeaderboardRow]) -> Result<Vec<u8>, serde_json::Error> {
+        match self {
+            ExportFormat::Csv => Ok(to_csv(rows).into_bytes()),
+            ExportFormat::Json => serde_json::to_vec_pretty(rows),
+        }
+    }
+}
+
+/// Same columns as the JSON export, with a header row
+fn to_csv(rows: &[LeaderboardRow]) -> String {
+    let mut csv = String::from("player,position,score,game,unix_time_stamp\n");
+    for row in rows {
+        csv += &format!(
+            "{},{},{},{},{}\n",
+            csv_field(&row.player),
+            row.position,
+            row.score,
+            csv_field(&row.game),
+            row.unix_time_stamp
+        );
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
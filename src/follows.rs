@@ -0,0 +1,145 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serenity::{
+    http::Http,
+    model::prelude::{ChannelId, UserId},
+};
+
+use crate::{diff::LeaderboardDiff, games::Game, store::LeaderboardStore};
+
+/// Where a follower wants to hear about changes, `None` is a DM
+type Recipient = (i64, Option<i64>);
+
+/// Compares a new submission with the valid one before it and tells everyone following
+/// a player that climbed, fell, entered or dropped off the leaderboard
+pub async fn notify_followers(
+    http: Arc<Http>,
+    store: Arc<dyn LeaderboardStore>,
+    game: Game,
+    submission_id: i64,
+) {
+    let diff = match previous_diff(store.as_ref(), &game, submission_id).await {
+        Ok(Some(diff)) => diff,
+        Ok(None) => return,
+        Err(why) => {
+            println!("Cannot compare submissions for followers: {}", why);
+            return;
+        }
+    };
+
+    // Keyed by the lowercase name, follows don't have to match the case of the leaderboard
+    let mut changes: HashMap<String, String> = HashMap::new();
+    for movement in diff.movements.iter().filter(|m| m.places() != 0) {
+        changes.insert(
+            movement.player.to_lowercase(),
+            movement.get_movement_string(&game.score_unit),
+        );
+    }
+    for row in &diff.new_entries {
+        changes.insert(
+            row.player.to_lowercase(),
+            format!(
+                "\n- {} entered at [{}]: {} {}",
+                row.player.replace('_', "\\_"),
+                row.position,
+                row.score,
+                game.score_unit
+            ),
+        );
+    }
+    for row in &diff.dropouts {
+        changes.insert(
+            row.player.to_lowercase(),
+            format!(
+                "\n- {} dropped off from [{}]",
+                row.player.replace('_', "\\_"),
+                row.position
+            ),
+        );
+    }
+    if changes.is_empty() {
+        return;
+    }
+
+    let players: Vec<String> = changes.keys().cloned().collect();
+    let follows = match store.followers_of(&players).await {
+        Ok(follows) => follows,
+        Err(why) => {
+            println!("Cannot load followers: {}", why);
+            return;
+        }
+    };
+
+    let mut messages: HashMap<Recipient, String> = HashMap::new();
+    for follow in follows {
+        let Some(change) = changes.get(&follow.player.to_lowercase()) else {
+            continue;
+        };
+        messages
+            .entry((follow.discord_user_id, follow.channel_id))
+            .or_insert_with(|| {
+                format!(
+                    "Changes on **{}** in submission {}:",
+                    game.name, submission_id
+                )
+            })
+            .push_str(change);
+    }
+
+    for ((user_id, channel_id), content) in messages {
+        if let Err(why) = deliver(&http, user_id, channel_id, content).await {
+            println!("Cannot notify follower {}: {}", user_id, why);
+        }
+    }
+}
+
+async fn previous_diff(
+    store: &dyn LeaderboardStore,
+    game: &Game,
+    submission_id: i64,
+) -> Result<Option<LeaderboardDiff>, sqlx::Error> {
+    let submissions = store.game_submissions(&game.name).await?;
+    let Some(previous) = submissions
+        .iter()
+        .find(|s| s.valid && s.unix_time_stamp < submission_id)
+    else {
+        return Ok(None);
+    };
+
+    let from = store
+        .submission_leaderboard(&game.name, previous.unix_time_stamp)
+        .await?;
+    let to = store
+        .submission_leaderboard(&game.name, submission_id)
+        .await?;
+    Ok(Some(LeaderboardDiff::between(
+        previous.unix_time_stamp,
+        from,
+        submission_id,
+        to,
+    )))
+}
+
+async fn deliver(
+    http: &Arc<Http>,
+    user_id: i64,
+    channel_id: Option<i64>,
+    content: String,
+) -> serenity::Result<()> {
+    let user_id = UserId(user_id as u64);
+    let channel = match channel_id {
+        Some(channel_id) => ChannelId(channel_id as u64),
+        None => user_id.create_dm_channel(http).await?.id,
+    };
+    channel
+        .send_message(http, |m| {
+            if channel_id.is_some() {
+                m.content(format!("<@{}> {}", user_id, content))
+                    .allowed_mentions(|mentions| mentions.users([user_id]))
+            } else {
+                m.content(content)
+            }
+        })
+        .await?;
+    Ok(())
+}
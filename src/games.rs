@@ -0,0 +1,114 @@
+use serde::Serialize;
+use serenity::builder::CreateApplicationCommandOption;
+use sqlx::{FromRow, Pool, Postgres};
+
+#[derive(FromRow, Serialize, Clone)]
+pub struct Game {
+    pub code: String,
+    pub name: String,
+    pub score_unit: String,
+    /// Whether a higher score means a better position
+    pub descending: bool,
+    pub active: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct GameRegistry {
+    games: Vec<Game>,
+}
+
+impl From<Vec<Game>> for GameRegistry {
+    fn from(games: Vec<Game>) -> Self {
+        GameRegistry { games }
+    }
+}
+
+impl GameRegistry {
+    pub async fn load(db: &Pool<Postgres>) -> Result<GameRegistry, sqlx::Error> {
+        let games = sqlx::query_as::<_, Game>(
+            "
+            SELECT
+                code,name,score_unit,descending,active
+            FROM
+                games
+            ORDER BY
+                name
+            ASC;",
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(GameRegistry::from(games))
+    }
+
+    pub fn all(&self) -> &[Game] {
+        &self.games
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Game> {
+        self.games.iter().filter(|game| game.active)
+    }
+
+    pub fn by_code(&self, code: &str) -> Option<&Game> {
+        self.games.iter().find(|game| game.code == code)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Game> {
+        self.games.iter().find(|game| game.name == name)
+    }
+
+    pub fn score_unit(&self, name: &str) -> &str {
+        self.by_name(name)
+            .map(|game| game.score_unit.as_str())
+            .unwrap_or("unknown")
+    }
+
+    /// Adds every active game as a choice, Discord allows at most 25 of them
+    pub fn add_choices<'a>(
+        &self,
+        option: &'a mut CreateApplicationCommandOption,
+    ) -> &'a mut CreateApplicationCommandOption {
+        for game in self.active().take(25) {
+            option.add_string_choice(&game.name, &game.code);
+        }
+        option
+    }
+
+    pub async fn add(db: &Pool<Postgres>, game: &Game) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "
+            INSERT INTO
+                games (code, name, score_unit, descending, active)
+            VALUES
+                ($1, $2, $3, $4, TRUE)
+            ON CONFLICT (code) DO UPDATE SET
+                name = EXCLUDED.name,
+                score_unit = EXCLUDED.score_unit,
+                descending = EXCLUDED.descending,
+                active = TRUE;",
+        )
+        .bind(&game.code)
+        .bind(&game.name)
+        .bind(&game.score_unit)
+        .bind(game.descending)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn retire(db: &Pool<Postgres>, code: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "
+            UPDATE
+                games
+            SET
+                active = FALSE
+            WHERE
+                code = $1;",
+        )
+        .bind(code)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
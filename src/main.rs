@@ -1,33 +1,96 @@
-use std::{collections::HashMap, sync::{Mutex, Arc}, time::Duration, fs::File, io::Read};
+use std::{collections::HashMap, env, sync::{Mutex, Arc}};
 
-use chrono::Utc;
-use serenity::{Client, prelude::{GatewayIntents, EventHandler, Context}, async_trait, model::prelude::{command::Command, Ready, GuildId, Message, component::ActionRowComponent}, utils::Colour, builder::{CreateEmbed, CreateInteractionResponseData, CreateComponents}};
+use serenity::{Client, prelude::{GatewayIntents, EventHandler, Context}, async_trait, model::prelude::{Ready, Message}, utils::Colour};
 use serenity::model::application::interaction::{Interaction, InteractionResponseType};
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-use tokio::time::sleep;
-use toml::Value;
 
+use crate::{config::Config, games::GameRegistry, paginator::PaginatorSession, store::{CachedStore, LeaderboardStore, PostgresStore}};
+
+mod admin;
+mod api;
+mod audit;
 mod commands;
+mod config;
 mod common;
+mod diff;
+mod export;
+mod follows;
+mod games;
+mod paginator;
+mod queries;
+mod render;
+mod similarity;
+mod store;
+mod submission;
+mod watcher;
 
 pub struct Bot {
     db: Pool<Postgres>,
-    owner_ids: Vec<u64>,
-    running_paginator: Arc<Mutex<HashMap<u64, Vec<String>>>>,
+    config: Config,
+    running_paginator: Arc<Mutex<HashMap<u64, PaginatorSession>>>,
+    games: Arc<Mutex<GameRegistry>>,
+    store: Arc<dyn LeaderboardStore>,
+}
+
+impl Bot {
+    fn games(&self) -> GameRegistry {
+        self.games.lock().map(|games| games.clone()).unwrap_or_default()
+    }
+
+    async fn reload_games(&self) -> Result<(), sqlx::Error> {
+        let registry = GameRegistry::load(&self.db).await?;
+        if let Ok(mut games) = self.games.lock() {
+            *games = registry;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl EventHandler for Bot {
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         if let Interaction::ApplicationCommand(command) = interaction {
-            let content = match &command.data.name.as_str() {
-                &"leaderboard" => commands::run(self, &command).await,
+            let deferred = command.data.name == "leaderboard" && commands::is_deferred(&command);
+            if deferred {
+                if let Err(why) = command.defer(&ctx.http).await {
+                    println!("Cannot defer slash command: {}", why);
+                    return;
+                }
+            }
+
+            let content = match command.data.name.as_str() {
+                "leaderboard" => commands::run(&ctx, self, &command).await,
+                "lbadmin" => admin::run(&ctx, self, &command).await,
                 _ => common::default_embed_from_content(
                     &command.user.name,
                     &command.user.avatar_url().unwrap_or_default(),
                     String::from("This command doesn't exist."), Colour::RED),
             };
 
+            if deferred {
+                let edit = commands::deferred_response(&content).unwrap_or_else(|why| {
+                    println!("Cannot defer slash command response: {}", why);
+                    commands::deferred_response(&common::default_embed_from_content(
+                        &command.user.name,
+                        &command.user.avatar_url().unwrap_or_default(),
+                        String::from("An error occurred trying to respond. Contact Fesa if this persists"), Colour::RED))
+                    .unwrap_or_default()
+                });
+
+                match command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    *response = edit;
+                    response
+                })
+                .await {
+                    Ok(response) => paginator::expire_later(self, ctx.http.clone(), response),
+                    Err(why) => {
+                        println!("Cannot respond to slash command: {}", why);
+                    },
+                }
+                return;
+            }
+
             match command
             .create_interaction_response(&ctx.http, |response| {
                 response
@@ -40,26 +103,7 @@ impl EventHandler for Bot {
             .await {
                 Ok(_) => {
                     if let Ok(response) = command.get_interaction_response(&ctx.http).await {
-                        let http = ctx.http.clone();
-                        let paginators = Arc::new(self.running_paginator.clone());
-                        tokio::spawn(async move {
-                            sleep(Duration::from_secs(60)).await;
-                            let Some(row) = response.components.get(0) else {return;};
-                            let Some(ActionRowComponent::Button(button)) = row.components.get(0) else {return;};
-                            let mut info = button.custom_id.as_ref().unwrap().split("_");
-                            let Some(key_string) = info.next() else {return;};
-                            let Ok(key) = key_string.parse::<u64>() else {return;};
-
-                            if let Ok(mut data) = paginators.lock() {
-                                data.remove(&key);
-                            }
-
-                            if let Err(why) = response.channel_id.edit_message(http, response.id, |m| {
-                                m.set_components(CreateComponents::default())
-                            }).await {
-                                println!("Cannot remove components: {}", why);
-                            }
-                        });
+                        paginator::expire_later(self, ctx.http.clone(), response);
                     }
                 },
                 Err(why) => {
@@ -67,139 +111,42 @@ impl EventHandler for Bot {
                 },
             }
             
-        } else if let Interaction::MessageComponent(component) = interaction {
-            let custom_id = &component.data.custom_id;
-            if !custom_id.contains("lb") {
-                return;
-            }
-
-            let Some(old_embed) = component.message.embeds.get(0) else {return;};
-            let Some(footer) = &old_embed.footer else {return;};
+        } else if let Interaction::Autocomplete(autocomplete) = interaction {
+            let names = commands::autocomplete(self, &autocomplete).await;
 
-            let mut message = CreateInteractionResponseData::default();
-
-            let mut info = custom_id.split("_");
-            let Some(key_string) = info.next() else {return;};
-            let Ok(key) = key_string.parse::<u64>() else {return;};
-            if let Ok(data) = self.running_paginator.lock() {
-                let Some(pages) = data.get(&key) else {return;};
-                let Some(index_string) = info.last() else {return;};
-                let Ok(index) = index_string.parse::<usize>() else {return;};
-                let Some(description) = pages.get(index) else {return;};
-            
-                let mut embed = CreateEmbed::default();
-                embed.footer(|f|
-                    f.text(&footer.text)
-                    .icon_url(component.user.avatar_url().unwrap_or_default()));
-                embed.timestamp(Utc::now().to_rfc3339());
-                embed.colour(Colour::from_rgb(106, 86, 246));
-                embed.description(description);
-
-                let next_button_id = format!("{}_next_lb_{}", key, index + 1);
-                let prev_button_id = format!("{}_prev_lb_{}", key, index - 1);
-                let max_len = pages.len();
-
-                message.add_embed(embed);
-                message.components(|component| {
-                    component.create_action_row(|action_row| {
-                        if index != 0 {
-                            action_row.create_button(|b| b.custom_id(&prev_button_id).emoji('◀'));
-                        }
-                        if index != max_len - 1 {
-                            action_row.create_button(|b| b.custom_id(&next_button_id).emoji('▶'));
-                        }
-                        action_row
-                    })
-                });
-                            }
-
-            if let Err(why) = component
-                .create_interaction_response(&ctx.http, |response| {
+            if let Err(why) = autocomplete
+                .create_autocomplete_response(&ctx.http, |response| {
+                    for name in &names {
+                        response.add_string_choice(name, name);
+                    }
                     response
-                        .kind(InteractionResponseType::UpdateMessage)
-                        .interaction_response_data(|m| {
-                            m.clone_from(&message);
-                            m
-                        })
                 }).await {
-                    println!("Cannot respond to slash command: {}", why);
+                    println!("Cannot respond to autocomplete: {}", why);
                 }
+        } else if let Interaction::MessageComponent(component) = interaction {
+            let custom_id = &component.data.custom_id;
+            if custom_id.starts_with("lbadmin_") {
+                admin::handle_component(&ctx, self, &component).await;
+                return;
+            }
+            if custom_id.starts_with(paginator::CUSTOM_ID_PREFIX) {
+                paginator::handle_component(&ctx, self, &component).await;
+            }
+        } else if let Interaction::ModalSubmit(modal) = interaction {
+            if modal.data.custom_id.starts_with(paginator::CUSTOM_ID_PREFIX) {
+                paginator::handle_modal(&ctx, self, &modal).await;
+            }
         }
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
+        admin::register_in_home_guild(&ctx, self).await;
     }
 
     async fn message(&self, ctx: Context, msg: Message) {
-        if !self.owner_ids.contains(&msg.author.id.as_u64()) 
-        && !msg.content.starts_with("?"){
-            return;
-        }
-
-        if msg.content.starts_with("?sync") {
-            let register_type = msg.content.strip_prefix("?sync ").unwrap_or("*");
-            match register_type {
-                "*" => {
-                    let guild_id = GuildId(781938561175388190);
-
-                    match GuildId::set_application_commands(&guild_id, &ctx.http, |commands| {
-                        commands
-                            .create_application_command(|command| commands::register(command))
-                    })
-                    .await {
-                        Ok(v) => {
-                            let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(
-                                format!("Synced {} commands to the guild.", v.len())
-                            )).await;
-                        },
-                        Err(err) => {
-                            let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(err.to_string())).await;
-                        }
-                    };
-                },
-                "~" => {
-                    match Command::create_global_application_command(&ctx.http, |command| {
-                        commands::register(command)
-                    })
-                    .await {
-                        Ok(_) => {
-                            let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(
-                                "Synced commands globally."
-                            )).await;
-                        },
-                        Err(err) => {
-                            let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(err.to_string())).await;
-                        }
-                    };
-                },
-                &_ => {}
-            }
-        } else if msg.content.starts_with("?invalidate") {
-            let Some(id_string) = msg.content.strip_prefix("?invalidate ") else {return;};
-            let Ok(id) = id_string.parse::<i64>() else {return;};
-
-            match sqlx::query("
-            UPDATE
-                submissions
-            SET
-                valid = false
-            WHERE
-                unix_time_stamp = $1;
-            ")
-            .bind(id)
-            .execute(&self.db)
-            .await {
-                Ok(_) => {
-                    let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(
-                        format!("Invalidated submission with ID: {}", id)
-                    )).await;
-                },
-                Err(err) => {
-                    let _ = msg.channel_id.send_message(&ctx.http, |m| m.content(err.to_string())).await;
-                },
-            }
-        } else if msg.content.starts_with("?hi") {
+        // The admin commands moved to /lbadmin, this one stays for everyone
+        if msg.content.starts_with("?hi") {
             let _ = msg.channel_id.send_message(&ctx.http, |m| m.content("Hello! <3")).await;
         }
     }
@@ -208,32 +155,61 @@ impl EventHandler for Bot {
 
 
 #[tokio::main]
-async fn main() -> Result<(), std::io::Error>  {
-    let mut file = File::open(String::from("config.toml"))?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
-    let config = content.parse::<Value>().unwrap();
+async fn main() {
+    let config = match Config::load("config.toml") {
+        Ok(config) => config,
+        Err(why) => {
+            println!("Config error: {}", why);
+            std::process::exit(1);
+        }
+    };
 
-    let database_url = config["database_url"].as_str().unwrap();
     let pool = PgPoolOptions::new()
-    .max_connections(5)
-    .connect(&database_url)
+    .max_connections(config.database.max_connections)
+    .acquire_timeout(config.database.acquire_timeout())
+    .connect(&config.database_url)
     .await
     .expect("Error building a connection pool");
+
+    if let Err(why) = sqlx::migrate!().run(&pool).await {
+        println!("Cannot apply migrations: {}", why);
+        std::process::exit(1);
+    }
+    if env::args().any(|arg| arg == "--migrate") {
+        println!("Migrations applied");
+        return;
+    }
     
-    let token = config["token"].as_str().unwrap();
+    if let Err(why) = queries::delete_expired_paginator_sessions(&pool).await {
+        println!("Cannot remove expired paginator sessions: {}", why);
+    }
+
+    let games = GameRegistry::load(&pool)
+    .await
+    .expect("Error loading the games");
 
-    let mut client = Client::builder(token, GatewayIntents::DIRECT_MESSAGES)
+    let store = Arc::new(CachedStore::load(PostgresStore::new(pool.clone())).await);
+    tokio::spawn(store::listen_for_changes(pool.clone(), store.clone()));
+
+    let games = Arc::new(Mutex::new(games));
+
+    if let Some(address) = config.api_address {
+        tokio::spawn(api::serve(address, pool.clone(), games.clone()));
+    }
+
+    let mut client = Client::builder(&config.token, GatewayIntents::DIRECT_MESSAGES)
         .event_handler(Bot {
             db: pool.clone(),
-            owner_ids: vec![474319793042751491, 322007790208155650],
-            running_paginator: Arc::new(Mutex::new(HashMap::new()))})
+            config,
+            running_paginator: Arc::new(Mutex::new(HashMap::new())),
+            games: games.clone(),
+            store: store.clone()})
         .await
         .expect("Error creating client");
 
+        tokio::spawn(watcher::watch(client.cache_and_http.http.clone(), store, games));
+
         if let Err(why) = client.start().await {
             println!("Client error: {:?}", why);
         }
-    
-    Ok(())
-}
\ No newline at end of file
+}
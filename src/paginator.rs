@@ -0,0 +1,605 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde_json::Value;
+use serenity::{
+    builder::{CreateActionRow, CreateComponents, CreateEmbed, CreateInteractionResponseData},
+    http::Http,
+    model::prelude::{
+        component::{ActionRowComponent, ButtonStyle, InputTextStyle},
+        interaction::{
+            message_component::MessageComponentInteraction, modal::ModalSubmitInteraction,
+            InteractionResponseType,
+        },
+        Message, ReactionType,
+    },
+    prelude::Context,
+};
+use tokio::time::sleep;
+
+use crate::{
+    commands::{self, BoardQuery},
+    store::LeaderboardStore,
+    Bot,
+};
+
+/// How long the controls of a paginated response keep working
+pub const SESSION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Every custom ID of the controls starts with this, followed by `_{key}_{action}_{page}`
+pub const CUSTOM_ID_PREFIX: &str = "paginator";
+
+#[derive(Clone)]
+pub struct PaginatorSession {
+    /// The user that ran the command
+    pub owner: u64,
+    /// The embeds as they're sent to Discord, page counter included
+    pub pages: Vec<Value>,
+    /// Unix timestamp in seconds
+    pub created_at: i64,
+    /// The leaderboard when the pages are one, adds a Find player control
+    pub find_player: Option<BoardQuery>,
+}
+
+impl PaginatorSession {
+    pub fn new(owner: u64, pages: Vec<CreateEmbed>) -> PaginatorSession {
+        let page_count = pages.len();
+        let pages = pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut embed)| {
+                if page_count > 1 {
+                    let counter = format!("Page {}/{}", index + 1, page_count);
+                    let text = match embed.0.get("footer").and_then(|f| f.get("text")) {
+                        Some(Value::String(text)) => format!("{} • {}", text, counter),
+                        _ => counter,
+                    };
+                    let footer = embed
+                        .0
+                        .entry("footer")
+                        .or_insert_with(|| Value::Object(Default::default()));
+                    if let Some(footer) = footer.as_object_mut() {
+                        footer.insert(String::from("text"), Value::String(text));
+                    }
+                }
+                Value::from(serenity::json::hashmap_to_json_map(embed.0))
+            })
+            .collect();
+
+        PaginatorSession {
+            owner,
+            pages,
+            created_at: Utc::now().timestamp(),
+            find_player: None,
+        }
+    }
+
+    /// The same pages for someone else, starting a fresh TTL
+    fn copy_for(&self, owner: u64) -> PaginatorSession {
+        PaginatorSession {
+            owner,
+            pages: self.pages.clone(),
+            created_at: Utc::now().timestamp(),
+            find_player: self.find_player.clone(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now().timestamp() - self.created_at >= SESSION_TTL.as_secs() as i64
+    }
+
+    /// Puts a page and the controls around it in the message, `index` is clamped to the last page
+    fn render(&self, key: u64, index: usize, message: &mut CreateInteractionResponseData) {
+        let last = self.pages.len().saturating_sub(1);
+        let index = index.min(last);
+        let Some(page) = self.pages.get(index) else {
+            return;
+        };
+        message.0.insert("embeds", Value::Array(vec![page.clone()]));
+        if last == 0 {
+            return;
+        }
+
+        let mut row = CreateActionRow::default();
+        control(&mut row, key, "first", 0, '⏮', index == 0);
+        control(
+            &mut row,
+            key,
+            "prev",
+            index.saturating_sub(1),
+            '◀',
+            index == 0,
+        );
+        row.create_button(|b| {
+            b.custom_id(custom_id(key, "jump", index))
+                .label("Go to page")
+                .style(ButtonStyle::Secondary)
+        });
+        control(
+            &mut row,
+            key,
+            "next",
+            (index + 1).min(last),
+            '▶',
+            index == last,
+        );
+        control(&mut row, key, "last", last, '⏭', index == last);
+
+        let mut components = CreateComponents::default();
+        components.add_action_row(row);
+        if self.find_player.is_some() {
+            components.create_action_row(|row| {
+                row.create_button(|b| {
+                    b.custom_id(custom_id(key, "find", index))
+                        .label("Find player")
+                        .style(ButtonStyle::Secondary)
+                })
+            });
+        }
+        message.set_components(components);
+    }
+}
+
+fn control(
+    row: &mut CreateActionRow,
+    key: u64,
+    action: &str,
+    page: usize,
+    emoji: char,
+    disabled: bool,
+) {
+    row.create_button(|b| {
+        b.custom_id(custom_id(key, action, page))
+            .emoji(ReactionType::Unicode(emoji.to_string()))
+            .style(ButtonStyle::Primary)
+            .disabled(disabled)
+    });
+}
+
+fn custom_id(key: u64, action: &str, page: usize) -> String {
+    format!("{}_{}_{}_{}", CUSTOM_ID_PREFIX, key, action, page)
+}
+
+/// The session key, action and page of a control's custom ID
+fn parse_custom_id(custom_id: &str) -> Option<(u64, &str, usize)> {
+    let mut parts = custom_id.strip_prefix(CUSTOM_ID_PREFIX)?.split('_').skip(1);
+    let key = parts.next()?.parse().ok()?;
+    let action = parts.next()?;
+    let page = parts.next()?.parse().ok()?;
+    Some((key, action, page))
+}
+
+/// Turns one embed into a page per description, keeping its footer, colour and timestamp
+pub fn description_pages(embed: &CreateEmbed, descriptions: Vec<String>) -> Vec<CreateEmbed> {
+    descriptions
+        .into_iter()
+        .map(|description| {
+            let mut page = embed.clone();
+            page.description(description);
+            page
+        })
+        .collect()
+}
+
+/// Shows the first page in the message, when there are more the session is kept around for the
+/// controls and saved to the database as well so they keep working after a restart
+pub fn paginate(
+    bot: &Bot,
+    owner: u64,
+    message: &mut CreateInteractionResponseData,
+    pages: Vec<CreateEmbed>,
+) {
+    show(bot, PaginatorSession::new(owner, pages), 0, message);
+}
+
+/// Like [`paginate`] for a session that's set up already, opening at `index`
+pub fn show(
+    bot: &Bot,
+    session: PaginatorSession,
+    index: usize,
+    message: &mut CreateInteractionResponseData,
+) {
+    if session.pages.len() < 2 {
+        session.render(0, 0, message);
+        return;
+    }
+
+    let key = start_session(bot, session.clone());
+    session.render(key, index, message);
+}
+
+/// Keeps a session around for its controls, returns the key they refer to
+fn start_session(bot: &Bot, session: PaginatorSession) -> u64 {
+    let key = next_key();
+    save_session(bot, key, session);
+    key
+}
+
+/// The current time in milliseconds, or one past the previous key when that's already taken, so
+/// sessions started in the same millisecond don't share a key and keys stay unique after a restart
+fn next_key() -> u64 {
+    static LAST_KEY: AtomicU64 = AtomicU64::new(0);
+    let now = Utc::now().timestamp_millis() as u64;
+    let previous = LAST_KEY
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_default();
+    now.max(previous + 1)
+}
+
+/// Saving under an existing key replaces that session
+fn save_session(bot: &Bot, key: u64, session: PaginatorSession) {
+    if let Ok(mut sessions) = bot.running_paginator.lock() {
+        sessions.insert(key, session.clone());
+    }
+    let store = bot.store.clone();
+    tokio::spawn(async move {
+        if let Err(why) = store.save_paginator_session(key as i64, &session).await {
+            println!("Cannot save paginator session: {}", why);
+        }
+    });
+}
+
+async fn forget_session(
+    sessions: &Mutex<HashMap<u64, PaginatorSession>>,
+    store: &dyn LeaderboardStore,
+    key: u64,
+) {
+    if let Ok(mut sessions) = sessions.lock() {
+        sessions.remove(&key);
+    }
+    if let Err(why) = store.delete_paginator_session(key as i64).await {
+        println!("Cannot remove paginator session: {}", why);
+    }
+}
+
+/// Looks in memory first and falls back to the sessions saved before a restart
+async fn load_session(bot: &Bot, key: u64) -> Option<PaginatorSession> {
+    let cached = bot
+        .running_paginator
+        .lock()
+        .ok()
+        .and_then(|sessions| sessions.get(&key).cloned());
+    let session = match cached {
+        Some(session) => session,
+        None => match bot.store.paginator_session(key as i64).await {
+            Ok(Some(session)) => {
+                if let Ok(mut sessions) = bot.running_paginator.lock() {
+                    sessions.insert(key, session.clone());
+                }
+                session
+            }
+            Ok(None) => return None,
+            Err(why) => {
+                println!("Cannot load paginator session: {}", why);
+                return None;
+            }
+        },
+    };
+    (!session.is_expired()).then_some(session)
+}
+
+/// Removes the controls and forgets the session once it expires
+pub fn expire_later(bot: &Bot, http: Arc<Http>, response: Message) {
+    let Some((key, _, _)) = response
+        .components
+        .first()
+        .and_then(|row| row.components.first())
+        .and_then(|component| match component {
+            ActionRowComponent::Button(button) => button.custom_id.as_deref(),
+            _ => None,
+        })
+        .and_then(parse_custom_id)
+    else {
+        return;
+    };
+
+    let sessions = bot.running_paginator.clone();
+    let store = bot.store.clone();
+    tokio::spawn(async move {
+        sleep(SESSION_TTL).await;
+        forget_session(&sessions, store.as_ref(), key).await;
+
+        if let Err(why) = response
+            .channel_id
+            .edit_message(http, response.id, |m| {
+                m.set_components(CreateComponents::default())
+            })
+            .await
+        {
+            println!("Cannot remove components: {}", why);
+        }
+    });
+}
+
+pub async fn handle_component(ctx: &Context, bot: &Bot, component: &MessageComponentInteraction) {
+    let Some((key, action, page)) = parse_custom_id(&component.data.custom_id) else {
+        return;
+    };
+    let Some(session) = load_session(bot, key).await else {
+        expired(ctx, component).await;
+        return;
+    };
+
+    if action == "copy" {
+        open_copy(ctx, bot, component, session, page).await;
+        return;
+    }
+    if *component.user.id.as_u64() != session.owner {
+        not_yours(ctx, component, key, session.owner).await;
+        return;
+    }
+
+    let result = if action == "find" {
+        component
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::Modal)
+                    .interaction_response_data(|m| {
+                        m.custom_id(custom_id(key, "player", 0))
+                            .title("Find player")
+                            .components(|c| {
+                                c.create_action_row(|row| {
+                                    row.create_input_text(|input| {
+                                        input
+                                            .custom_id("player")
+                                            .label("Player")
+                                            .style(InputTextStyle::Short)
+                                            .min_length(2)
+                                            .max_length(16)
+                                            .required(true)
+                                    })
+                                })
+                            })
+                    })
+            })
+            .await
+    } else if action == "jump" {
+        component
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::Modal)
+                    .interaction_response_data(|m| {
+                        m.custom_id(custom_id(key, "goto", 0))
+                            .title("Go to page")
+                            .components(|c| {
+                                c.create_action_row(|row| {
+                                    row.create_input_text(|input| {
+                                        input
+                                            .custom_id("page")
+                                            .label(format!("Page (1-{})", session.pages.len()))
+                                            .style(InputTextStyle::Short)
+                                            .min_length(1)
+                                            .max_length(4)
+                                            .required(true)
+                                    })
+                                })
+                            })
+                    })
+            })
+            .await
+    } else {
+        component
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|m| {
+                        session.render(key, page, m);
+                        m
+                    })
+            })
+            .await
+    };
+
+    if let Err(why) = result {
+        println!("Cannot respond to paginator controls: {}", why);
+    }
+}
+
+/// Handles the page number typed in the modal of the jump control, and the player typed in the
+/// one of the Find player control
+pub async fn handle_modal(ctx: &Context, bot: &Bot, modal: &ModalSubmitInteraction) {
+    let Some((key, action, _)) = parse_custom_id(&modal.data.custom_id) else {
+        return;
+    };
+    let Some(mut session) = load_session(bot, key).await else {
+        modal_reply(
+            ctx,
+            modal,
+            String::from("This view expired, run the command again."),
+        )
+        .await;
+        return;
+    };
+
+    let value = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) => Some(input.value.trim().to_owned()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let page = match (action, &session.find_player) {
+        ("player", Some(query)) => {
+            match commands::find_player_pages(bot, &modal.user, query, &value).await {
+                Ok((pages, index)) => {
+                    // The latest board with the player in bold, under the same key so the
+                    // controls expire as they would have
+                    let mut replacement = PaginatorSession::new(session.owner, pages);
+                    replacement.created_at = session.created_at;
+                    replacement.find_player = session.find_player.clone();
+                    save_session(bot, key, replacement.clone());
+                    session = replacement;
+                    index
+                }
+                Err(content) => {
+                    modal_reply(ctx, modal, content).await;
+                    return;
+                }
+            }
+        }
+        _ => match value.parse::<usize>() {
+            Ok(page) if (1..=session.pages.len()).contains(&page) => page - 1,
+            _ => {
+                modal_reply(
+                    ctx,
+                    modal,
+                    format!(
+                        "`{}` isn't a page, pick one from 1 to {}.",
+                        value,
+                        session.pages.len()
+                    ),
+                )
+                .await;
+                return;
+            }
+        },
+    };
+
+    if let Err(why) = modal
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|m| {
+                    session.render(key, page, m);
+                    m
+                })
+        })
+        .await
+    {
+        println!("Cannot respond to paginator modal: {}", why);
+    }
+}
+
+async fn modal_reply(ctx: &Context, modal: &ModalSubmitInteraction, content: String) {
+    if let Err(why) = modal
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|m| m.ephemeral(true).content(content))
+        })
+        .await
+    {
+        println!("Cannot respond to paginator modal: {}", why);
+    }
+}
+
+async fn expired(ctx: &Context, component: &MessageComponentInteraction) {
+    if let Err(why) = component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|m| {
+                    m.ephemeral(true)
+                        .content("This view expired, run the command again.")
+                })
+        })
+        .await
+    {
+        println!("Cannot respond to paginator controls: {}", why);
+    }
+}
+
+/// The page a message is showing, the jump control refers to it
+fn current_page(message: &Message) -> usize {
+    message
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::Button(button) => button
+                .custom_id
+                .as_deref()
+                .and_then(parse_custom_id)
+                .filter(|(_, action, _)| *action == "jump")
+                .map(|(_, _, page)| page),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Only the owner can flip through a session, everyone else gets to open their own copy
+async fn not_yours(ctx: &Context, component: &MessageComponentInteraction, key: u64, owner: u64) {
+    let page = current_page(&component.message);
+    if let Err(why) = component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|m| {
+                    m.ephemeral(true)
+                        .content(format!(
+                            "Only <@{}> can flip through these pages, run the command yourself or open your own copy.",
+                            owner
+                        ))
+                        .components(|c| {
+                            c.create_action_row(|row| {
+                                row.create_button(|b| {
+                                    b.custom_id(custom_id(key, "copy", page))
+                                        .label("Open my own copy")
+                                        .style(ButtonStyle::Secondary)
+                                })
+                            })
+                        })
+                })
+        })
+        .await
+    {
+        println!("Cannot respond to paginator controls: {}", why);
+    }
+}
+
+/// Turns the "run the command yourself" reply into a paginator of the user's own, at the same page
+async fn open_copy(
+    ctx: &Context,
+    bot: &Bot,
+    component: &MessageComponentInteraction,
+    session: PaginatorSession,
+    page: usize,
+) {
+    let copy = session.copy_for(*component.user.id.as_u64());
+    let key = start_session(bot, copy.clone());
+
+    if let Err(why) = component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|m| {
+                    copy.render(key, page, m);
+                    m.content("")
+                })
+        })
+        .await
+    {
+        println!("Cannot respond to paginator controls: {}", why);
+        return;
+    }
+
+    // Ephemeral messages can only be edited through the interaction that created them
+    let http = ctx.http.clone();
+    let sessions = bot.running_paginator.clone();
+    let store = bot.store.clone();
+    let component = component.clone();
+    tokio::spawn(async move {
+        sleep(SESSION_TTL).await;
+        forget_session(&sessions, store.as_ref(), key).await;
+
+        if let Err(why) = component
+            .edit_original_interaction_response(http, |m| m.components(|c| c))
+            .await
+        {
+            println!("Cannot remove components: {}", why);
+        }
+    });
+}
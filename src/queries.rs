@@ -0,0 +1,959 @@
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Postgres};
+
+use crate::{
+    commands::{HistoryRow, LeaderboardRow},
+    paginator::{PaginatorSession, SESSION_TTL},
+    submission::SubmissionRow,
+};
+
+#[derive(FromRow, Serialize, Clone)]
+pub struct Submission {
+    pub game: String,
+    pub unix_time_stamp: i64,
+    pub valid: bool,
+}
+
+/// The leaderboard of the most recent valid submission of a game
+pub async fn latest_game_leaderboard(
+    db: &Pool<Postgres>,
+    game_name: &str,
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    sqlx::query_as::<_, LeaderboardRow>(
+        "
+        SELECT 
+            player,position,score,game,unix_time_stamp
+        FROM
+            leaderboards
+        WHERE
+            game = $1
+        AND
+            unix_time_stamp
+        = (SELECT
+                MAX(unix_time_stamp)
+            FROM
+                submissions
+            WHERE
+                valid = TRUE
+            AND
+                game = $1)
+        ORDER BY
+            position
+        ASC;",
+    )
+    .bind(game_name)
+    .fetch_all(db)
+    .await
+}
+
+/// Like [`latest_game_leaderboard`], only the positions between `from` and `to`, inclusive
+pub async fn latest_game_leaderboard_between(
+    db: &Pool<Postgres>,
+    game_name: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    sqlx::query_as::<_, LeaderboardRow>(
+        "
+        SELECT 
+            player,position,score,game,unix_time_stamp
+        FROM
+            leaderboards
+        WHERE
+            game = $1
+        AND
+            unix_time_stamp
+        = (SELECT
+                MAX(unix_time_stamp)
+            FROM
+                submissions
+            WHERE
+                valid = TRUE
+            AND
+                game = $1)
+        AND
+            position BETWEEN $2 AND $3
+        ORDER BY
+            position
+        ASC;",
+    )
+    .bind(game_name)
+    .bind(from)
+    .bind(to)
+    .fetch_all(db)
+    .await
+}
+
+/// Every leaderboard a player is on, looking only at the most recent valid submission per game
+pub async fn latest_player_leaderboards(
+    db: &Pool<Postgres>,
+    player_name: &str,
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    sqlx::query_as::<_, LeaderboardRow>(
+        "
+        SELECT 
+            player,position,score,game,unix_time_stamp
+        FROM
+            leaderboards
+        WHERE
+            (game, unix_time_stamp)
+        IN (SELECT
+                game, MAX(unix_time_stamp)
+            FROM
+                submissions
+            WHERE
+                valid = TRUE
+            GROUP BY
+                game)
+        AND
+            player = $1
+        ORDER BY
+            position
+        ASC;",
+    )
+    .bind(player_name)
+    .fetch_all(db)
+    .await
+}
+
+/// Case-insensitive prefix search over the players of the most recent valid submissions
+pub async fn player_names_starting_with(
+    db: &Pool<Postgres>,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<String>, sqlx::Error> {
+    let pattern = format!(
+        "{}%",
+        prefix
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    sqlx::query_scalar::<_, String>(
+        "
+        SELECT DISTINCT
+            player
+        FROM
+            leaderboards
+        WHERE
+            (game, unix_time_stamp)
+        IN (SELECT
+                game, MAX(unix_time_stamp)
+            FROM
+                submissions
+            WHERE
+                valid = TRUE
+            GROUP BY
+                game)
+        AND
+            LOWER(player) LIKE $1
+        ORDER BY
+            player
+        ASC
+        LIMIT $2;",
+    )
+    .bind(pattern)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+/// The last valid submission per game a player was on
+pub async fn player_last_seen(
+    db: &Pool<Postgres>,
+    player_name: &str,
+) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, i64)>(
+        "
+        SELECT
+            leaderboards.game, MAX(leaderboards.unix_time_stamp)
+        FROM
+            leaderboards
+        JOIN
+            submissions
+        ON
+            submissions.game = leaderboards.game
+        AND
+            submissions.unix_time_stamp = leaderboards.unix_time_stamp
+        WHERE
+            submissions.valid = TRUE
+        AND
+            leaderboards.player = $1
+        GROUP BY
+            leaderboards.game
+        ORDER BY
+            leaderboards.game
+        ASC;",
+    )
+    .bind(player_name)
+    .fetch_all(db)
+    .await
+}
+
+/// Every game with at least one submission, whether it's registered or not
+pub async fn submitted_game_names(db: &Pool<Postgres>) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "
+        SELECT DISTINCT
+            game
+        FROM
+            submissions
+        ORDER BY
+            game
+        ASC;",
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Every player that has been on any valid submission
+pub async fn all_player_names(db: &Pool<Postgres>) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "
+        SELECT DISTINCT
+            leaderboards.player
+        FROM
+            leaderboards
+        JOIN
+            submissions
+        ON
+            submissions.game = leaderboards.game
+        AND
+            submissions.unix_time_stamp = leaderboards.unix_time_stamp
+        WHERE
+            submissions.valid = TRUE;",
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// A player's position on every valid submission of a game, newest first
+pub async fn player_history(
+    db: &Pool<Postgres>,
+    game_name: &str,
+    player_name: &str,
+) -> Result<Vec<HistoryRow>, sqlx::Error> {
+    sqlx::query_as::<_, HistoryRow>(
+        "
+        SELECT
+            submissions.unix_time_stamp,leaderboards.position,leaderboards.score
+        FROM
+            submissions
+        LEFT JOIN
+            leaderboards
+        ON
+            leaderboards.game = submissions.game
+        AND
+            leaderboards.unix_time_stamp = submissions.unix_time_stamp
+        AND
+            leaderboards.player = $2
+        WHERE
+            submissions.valid = TRUE
+        AND
+            submissions.game = $1
+        ORDER BY
+            submissions.unix_time_stamp
+        DESC;",
+    )
+    .bind(game_name)
+    .bind(player_name)
+    .fetch_all(db)
+    .await
+}
+
+/// Every submission of a game, valid or not, newest first
+pub async fn game_submissions(
+    db: &Pool<Postgres>,
+    game_name: &str,
+) -> Result<Vec<Submission>, sqlx::Error> {
+    sqlx::query_as::<_, Submission>(
+        "
+        SELECT
+            game,unix_time_stamp,valid
+        FROM
+            submissions
+        WHERE
+            game = $1
+        ORDER BY
+            unix_time_stamp
+        DESC;",
+    )
+    .bind(game_name)
+    .fetch_all(db)
+    .await
+}
+
+/// Valid submissions of any game that haven't been announced yet, oldest first. Submissions made
+/// valid again or added with an older ID are in here as well
+pub async fn unannounced_submissions(db: &Pool<Postgres>) -> Result<Vec<Submission>, sqlx::Error> {
+    sqlx::query_as::<_, Submission>(
+        "
+        SELECT
+            game,unix_time_stamp,valid
+        FROM
+            submissions
+        WHERE
+            valid = TRUE
+        AND
+            announced = FALSE
+        ORDER BY
+            unix_time_stamp
+        ASC;",
+    )
+    .fetch_all(db)
+    .await
+}
+
+pub async fn mark_submission_announced(
+    db: &Pool<Postgres>,
+    game_name: &str,
+    submission_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        UPDATE
+            submissions
+        SET
+            announced = TRUE
+        WHERE
+            game = $1
+        AND
+            unix_time_stamp = $2;",
+    )
+    .bind(game_name)
+    .bind(submission_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+#[derive(FromRow)]
+pub struct SubmissionSummary {
+    pub game: String,
+    pub unix_time_stamp: i64,
+    pub valid: bool,
+    /// Discord ID of whoever used /leaderboard submit, unknown for older submissions
+    pub submitter: Option<i64>,
+    pub row_count: i64,
+}
+
+impl SubmissionSummary {
+    pub fn get_summary_string(&self) -> String {
+        format!(
+            "\n- {} {}: {} players{}",
+            self.unix_time_stamp,
+            self.game,
+            self.row_count,
+            if self.valid { "" } else { " (invalid)" }
+        )
+    }
+}
+
+/// Every submission with its amount of leaderboard rows, optionally only for one game
+pub async fn submission_summaries(
+    db: &Pool<Postgres>,
+    game_name: Option<&str>,
+) -> Result<Vec<SubmissionSummary>, sqlx::Error> {
+    sqlx::query_as::<_, SubmissionSummary>(
+        "
+        SELECT
+            submissions.game,submissions.unix_time_stamp,submissions.valid,submissions.submitter,
+            COUNT(leaderboards.player) AS row_count
+        FROM
+            submissions
+        LEFT JOIN
+            leaderboards
+        ON
+            leaderboards.game = submissions.game
+        AND
+            leaderboards.unix_time_stamp = submissions.unix_time_stamp
+        WHERE
+            $1::TEXT IS NULL
+        OR
+            submissions.game = $1
+        GROUP BY
+            submissions.game,submissions.unix_time_stamp,submissions.valid,submissions.submitter
+        ORDER BY
+            submissions.unix_time_stamp
+        DESC;",
+    )
+    .bind(game_name)
+    .fetch_all(db)
+    .await
+}
+
+pub async fn submission_summary(
+    db: &Pool<Postgres>,
+    submission_id: i64,
+) -> Result<Option<SubmissionSummary>, sqlx::Error> {
+    sqlx::query_as::<_, SubmissionSummary>(
+        "
+        SELECT
+            submissions.game,submissions.unix_time_stamp,submissions.valid,submissions.submitter,
+            COUNT(leaderboards.player) AS row_count
+        FROM
+            submissions
+        LEFT JOIN
+            leaderboards
+        ON
+            leaderboards.game = submissions.game
+        AND
+            leaderboards.unix_time_stamp = submissions.unix_time_stamp
+        WHERE
+            submissions.unix_time_stamp = $1
+        GROUP BY
+            submissions.game,submissions.unix_time_stamp,submissions.valid,submissions.submitter
+        LIMIT 1;",
+    )
+    .bind(submission_id)
+    .fetch_optional(db)
+    .await
+}
+
+pub async fn set_submission_validity(
+    db: &Pool<Postgres>,
+    submission_id: i64,
+    valid: bool,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "
+        UPDATE
+            submissions
+        SET
+            valid = $2
+        WHERE
+            unix_time_stamp = $1;",
+    )
+    .bind(submission_id)
+    .bind(valid)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Removes a submission and its leaderboard rows, returns the amount of submissions removed
+pub async fn delete_submission(
+    db: &Pool<Postgres>,
+    submission_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let mut transaction = db.begin().await?;
+
+    sqlx::query(
+        "
+        DELETE FROM
+            leaderboards
+        WHERE
+            (game, unix_time_stamp)
+        IN (SELECT
+                game, unix_time_stamp
+            FROM
+                submissions
+            WHERE
+                unix_time_stamp = $1);",
+    )
+    .bind(submission_id)
+    .execute(&mut transaction)
+    .await?;
+
+    let result = sqlx::query(
+        "
+        DELETE FROM
+            submissions
+        WHERE
+            unix_time_stamp = $1;",
+    )
+    .bind(submission_id)
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn submission(
+    db: &Pool<Postgres>,
+    submission_id: i64,
+) -> Result<Option<Submission>, sqlx::Error> {
+    sqlx::query_as::<_, Submission>(
+        "
+        SELECT
+            game,unix_time_stamp,valid
+        FROM
+            submissions
+        WHERE
+            unix_time_stamp = $1
+        LIMIT 1;",
+    )
+    .bind(submission_id)
+    .fetch_optional(db)
+    .await
+}
+
+pub async fn submission_leaderboard(
+    db: &Pool<Postgres>,
+    game_name: &str,
+    submission_id: i64,
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    sqlx::query_as::<_, LeaderboardRow>(
+        "
+        SELECT
+            player,position,score,game,unix_time_stamp
+        FROM
+            leaderboards
+        WHERE
+            game = $1
+        AND
+            unix_time_stamp = $2
+        ORDER BY
+            position
+        ASC;",
+    )
+    .bind(game_name)
+    .bind(submission_id)
+    .fetch_all(db)
+    .await
+}
+
+/// Saves a submission under `submission_id`, or one past the newest ID of any game when that isn't
+/// newer, so IDs stay unique across games. Returns the ID it was saved under
+pub async fn insert_submission(
+    db: &Pool<Postgres>,
+    game_name: &str,
+    submission_id: i64,
+    submitter: u64,
+    rows: &[SubmissionRow],
+) -> Result<i64, sqlx::Error> {
+    let mut transaction = db.begin().await?;
+
+    // Submissions made at the same time wait for each other, so they can't pick the same ID
+    sqlx::query("LOCK TABLE submissions IN SHARE ROW EXCLUSIVE MODE;")
+        .execute(&mut transaction)
+        .await?;
+
+    let submission_id = sqlx::query_scalar::<_, i64>(
+        "
+        INSERT INTO
+            submissions (game, unix_time_stamp, valid, submitter)
+        SELECT
+            $1, GREATEST($2, COALESCE(MAX(unix_time_stamp) + 1, $2)), TRUE, $3
+        FROM
+            submissions
+        RETURNING
+            unix_time_stamp;",
+    )
+    .bind(game_name)
+    .bind(submission_id)
+    .bind(submitter as i64)
+    .fetch_one(&mut transaction)
+    .await?;
+
+    sqlx::query(
+        "
+        INSERT INTO
+            leaderboards (player, position, score, game, unix_time_stamp)
+        SELECT
+            player, position, score, $4, $5
+        FROM
+            UNNEST($1::TEXT[], $2::INTEGER[], $3::INTEGER[]) AS rows (player, position, score);",
+    )
+    .bind(
+        rows.iter()
+            .map(|row| row.player.clone())
+            .collect::<Vec<String>>(),
+    )
+    .bind(rows.iter().map(|row| row.position).collect::<Vec<i32>>())
+    .bind(rows.iter().map(|row| row.score).collect::<Vec<i32>>())
+    .bind(game_name)
+    .bind(submission_id)
+    .execute(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+    Ok(submission_id)
+}
+
+#[derive(FromRow, Clone)]
+pub struct Follow {
+    pub discord_user_id: i64,
+    pub player: String,
+    pub channel_id: Option<i64>,
+}
+
+impl Follow {
+    pub fn get_follow_string(&self) -> String {
+        match self.channel_id {
+            Some(channel_id) => format!(
+                "\n- {} (in <#{}>)",
+                self.player.replace('_', "\\_"),
+                channel_id
+            ),
+            None => format!("\n- {} (DM)", self.player.replace('_', "\\_")),
+        }
+    }
+}
+
+/// Following a player again, in any case, only changes the name and where the notifications go
+pub async fn insert_follow(
+    db: &Pool<Postgres>,
+    discord_user_id: i64,
+    player_name: &str,
+    channel_id: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        INSERT INTO
+            follows (discord_user_id, player, channel_id)
+        VALUES
+            ($1, $2, $3)
+        ON CONFLICT (discord_user_id, LOWER(player)) DO UPDATE SET
+            player = EXCLUDED.player,
+            channel_id = EXCLUDED.channel_id;",
+    )
+    .bind(discord_user_id)
+    .bind(player_name)
+    .bind(channel_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_follow(
+    db: &Pool<Postgres>,
+    discord_user_id: i64,
+    player_name: &str,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "
+        DELETE FROM
+            follows
+        WHERE
+            discord_user_id = $1
+        AND
+            LOWER(player) = LOWER($2);",
+    )
+    .bind(discord_user_id)
+    .bind(player_name)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected())
+}
+
+pub async fn user_follows(
+    db: &Pool<Postgres>,
+    discord_user_id: i64,
+) -> Result<Vec<Follow>, sqlx::Error> {
+    sqlx::query_as::<_, Follow>(
+        "
+        SELECT
+            discord_user_id,player,channel_id
+        FROM
+            follows
+        WHERE
+            discord_user_id = $1
+        ORDER BY
+            player
+        ASC;",
+    )
+    .bind(discord_user_id)
+    .fetch_all(db)
+    .await
+}
+
+/// Names are matched regardless of case
+pub async fn followers_of(
+    db: &Pool<Postgres>,
+    player_names: &[String],
+) -> Result<Vec<Follow>, sqlx::Error> {
+    let player_names: Vec<String> = player_names
+        .iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    sqlx::query_as::<_, Follow>(
+        "
+        SELECT
+            discord_user_id,player,channel_id
+        FROM
+            follows
+        WHERE
+            LOWER(player) = ANY($1);",
+    )
+    .bind(player_names)
+    .fetch_all(db)
+    .await
+}
+
+pub async fn linked_player(
+    db: &Pool<Postgres>,
+    discord_user_id: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "
+        SELECT
+            player
+        FROM
+            linked_accounts
+        WHERE
+            discord_user_id = $1;",
+    )
+    .bind(discord_user_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// A Discord account links to one player, linking again replaces it
+pub async fn link_account(
+    db: &Pool<Postgres>,
+    discord_user_id: i64,
+    player_name: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        INSERT INTO
+            linked_accounts (discord_user_id, player)
+        VALUES
+            ($1, $2)
+        ON CONFLICT (discord_user_id) DO UPDATE SET
+            player = EXCLUDED.player;",
+    )
+    .bind(discord_user_id)
+    .bind(player_name)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn unlink_account(db: &Pool<Postgres>, discord_user_id: i64) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "
+        DELETE FROM
+            linked_accounts
+        WHERE
+            discord_user_id = $1;",
+    )
+    .bind(discord_user_id)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected())
+}
+
+/// Every channel, across guilds, new snapshots of a game are posted in
+pub async fn announcement_channels(
+    db: &Pool<Postgres>,
+    game_name: &str,
+) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "
+        SELECT
+            channel_id
+        FROM
+            announcement_channels
+        WHERE
+            game = $1;",
+    )
+    .bind(game_name)
+    .fetch_all(db)
+    .await
+}
+
+/// A guild has at most one announcement channel per game
+pub async fn set_announcement_channel(
+    db: &Pool<Postgres>,
+    guild_id: i64,
+    game_name: &str,
+    channel_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        INSERT INTO
+            announcement_channels (guild_id, game, channel_id)
+        VALUES
+            ($1, $2, $3)
+        ON CONFLICT (guild_id, game) DO UPDATE SET
+            channel_id = EXCLUDED.channel_id;",
+    )
+    .bind(guild_id)
+    .bind(game_name)
+    .bind(channel_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_announcement_channel(
+    db: &Pool<Postgres>,
+    guild_id: i64,
+    game_name: &str,
+) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "
+        DELETE FROM
+            announcement_channels
+        WHERE
+            guild_id = $1
+        AND
+            game = $2;",
+    )
+    .bind(guild_id)
+    .bind(game_name)
+    .execute(db)
+    .await
+    .map(|result| result.rows_affected())
+}
+
+/// Sessions older than this can't be paged through anymore
+fn paginator_session_ttl() -> String {
+    format!("{} seconds", SESSION_TTL.as_secs())
+}
+
+/// Pages are kept as the JSON of their embeds, saving under an existing key replaces that session
+pub async fn insert_paginator_session(
+    db: &Pool<Postgres>,
+    key: i64,
+    session: &PaginatorSession,
+) -> Result<(), sqlx::Error> {
+    let pages: Vec<String> = session.pages.iter().map(|page| page.to_string()).collect();
+    sqlx::query(
+        "
+        INSERT INTO
+            paginator_sessions (id, owner, pages, created_at, find_player)
+        VALUES
+            ($1, $2, $3, to_timestamp($4), $5)
+        ON CONFLICT (id) DO UPDATE SET
+            owner = EXCLUDED.owner,
+            pages = EXCLUDED.pages,
+            created_at = EXCLUDED.created_at,
+            find_player = EXCLUDED.find_player;",
+    )
+    .bind(key)
+    .bind(session.owner as i64)
+    .bind(pages)
+    .bind(session.created_at as f64)
+    .bind(
+        session
+            .find_player
+            .as_ref()
+            .and_then(|query| serde_json::to_string(query).ok()),
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn paginator_session(
+    db: &Pool<Postgres>,
+    key: i64,
+) -> Result<Option<PaginatorSession>, sqlx::Error> {
+    let row = sqlx::query_as::<_, (i64, Vec<String>, i64, Option<String>)>(
+        "
+        SELECT
+            owner,
+            pages,
+            EXTRACT(EPOCH FROM created_at)::BIGINT,
+            find_player
+        FROM
+            paginator_sessions
+        WHERE
+            id = $1
+        AND
+            created_at > now() - $2::interval;",
+    )
+    .bind(key)
+    .bind(paginator_session_ttl())
+    .fetch_optional(db)
+    .await?;
+
+    Ok(
+        row.map(|(owner, pages, created_at, find_player)| PaginatorSession {
+            owner: owner as u64,
+            pages: pages
+                .iter()
+                .filter_map(|page| serde_json::from_str(page).ok())
+                .collect(),
+            created_at,
+            find_player: find_player.and_then(|query| serde_json::from_str(&query).ok()),
+        }),
+    )
+}
+
+pub async fn delete_paginator_session(db: &Pool<Postgres>, key: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        DELETE FROM
+            paginator_sessions
+        WHERE
+            id = $1;",
+    )
+    .bind(key)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_expired_paginator_sessions(db: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "
+        DELETE FROM
+            paginator_sessions
+        WHERE
+            created_at <= now() - $1::interval;",
+    )
+    .bind(paginator_session_ttl())
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[derive(FromRow, Clone)]
+pub struct AuditEntry {
+    pub actor: i64,
+    pub action: String,
+    pub target: String,
+    pub created_at: i64,
+}
+
+impl AuditEntry {
+    pub fn get_audit_string(&self) -> String {
+        format!(
+            "\n- <t:{}:f> <@{}> {} {}",
+            self.created_at, self.actor, self.action, self.target
+        )
+    }
+}
+
+pub async fn insert_audit_entry(
+    db: &Pool<Postgres>,
+    actor: i64,
+    action: &str,
+    target: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "
+        INSERT INTO
+            audit_log (actor, action, target, created_at)
+        VALUES
+            ($1, $2, $3, now());",
+    )
+    .bind(actor)
+    .bind(action)
+    .bind(target)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn audit_entries(db: &Pool<Postgres>) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    sqlx::query_as::<_, AuditEntry>(
+        "
+        SELECT
+            actor,action,target,EXTRACT(EPOCH FROM created_at)::BIGINT AS created_at
+        FROM
+            audit_log
+        ORDER BY
+            audit_log.created_at
+        DESC;",
+    )
+    .fetch_all(db)
+    .await
+}
@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, Rgb, RgbImage};
+use imageproc::{
+    drawing::{draw_filled_rect_mut, draw_text_mut, text_size},
+    rect::Rect,
+};
+use rusttype::{Font, Scale};
+
+static FONT: &[u8] = include_bytes!("../assets/DejaVuSansMono.ttf");
+
+pub const IMAGE_NAME: &str = "leaderboard.png";
+
+const WIDTH: u32 = 640;
+const ROW_HEIGHT: u32 = 32;
+const HEADER_HEIGHT: u32 = 56;
+const FOOTER_HEIGHT: u32 = 36;
+const PADDING: i32 = 16;
+
+const BACKGROUND: Rgb<u8> = Rgb([43, 45, 49]);
+const STRIPE: Rgb<u8> = Rgb([49, 51, 56]);
+const ACCENT: Rgb<u8> = Rgb([106, 86, 246]);
+const TEXT: Rgb<u8> = Rgb([242, 243, 245]);
+const MUTED: Rgb<u8> = Rgb([181, 186, 193]);
+
+pub struct ImageRow<'a> {
+    pub position: i32,
+    pub name: &'a str,
+    pub score: i32,
+    pub score_unit: &'a str,
+}
+
+/// Draws a leaderboard page as a PNG, one line per row under a title bar
+pub fn render_leaderboard(
+    title: &str,
+    rows: &[ImageRow],
+    submission_id: i64,
+) -> Result<Vec<u8>, image::ImageError> {
+    let font = Font::try_from_bytes(FONT).expect("The embedded font should be valid");
+    let height = HEADER_HEIGHT + ROW_HEIGHT * rows.len() as u32 + FOOTER_HEIGHT;
+    let mut image = RgbImage::from_pixel(WIDTH, height, BACKGROUND);
+
+    draw_filled_rect_mut(
+        &mut image,
+        Rect::at(0, 0).of_size(WIDTH, HEADER_HEIGHT),
+        ACCENT,
+    );
+    draw_text_mut(
+        &mut image,
+        TEXT,
+        PADDING,
+        14,
+        Scale::uniform(28.0),
+        &font,
+        title,
+    );
+
+    let scale = Scale::uniform(20.0);
+    for (i, row) in rows.iter().enumerate() {
+        let top = (HEADER_HEIGHT + ROW_HEIGHT * i as u32) as i32;
+        if i % 2 == 1 {
+            draw_filled_rect_mut(
+                &mut image,
+                Rect::at(0, top).of_size(WIDTH, ROW_HEIGHT),
+                STRIPE,
+            );
+        }
+
+        let y = top + 6;
+        draw_text_mut(
+            &mut image,
+            MUTED,
+            PADDING,
+            y,
+            scale,
+            &font,
+            &format!("#{}", row.position),
+        );
+        draw_text_mut(&mut image, TEXT, PADDING + 72, y, scale, &font, row.name);
+
+        let score = format!("{} {}", row.score, row.score_unit);
+        let (score_width, _) = text_size(scale, &font, &score);
+        draw_text_mut(
+            &mut image,
+            TEXT,
+            WIDTH as i32 - PADDING - score_width,
+            y,
+            scale,
+            &font,
+            &score,
+        );
+    }
+
+    draw_text_mut(
+        &mut image,
+        MUTED,
+        PADDING,
+        (height - FOOTER_HEIGHT) as i32 + 8,
+        Scale::uniform(16.0),
+        &font,
+        &format!("Submission ID: {}", submission_id),
+    );
+
+    let mut png = Cursor::new(vec![]);
+    image.write_to(&mut png, ImageOutputFormat::Png)?;
+    Ok(png.into_inner())
+}
@@ -0,0 +1,366 @@
+use serenity::async_trait;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    commands::{HistoryRow, LeaderboardRow},
+    paginator::PaginatorSession,
+    queries::{self, AuditEntry, Follow, Submission, SubmissionSummary},
+    submission::SubmissionRow,
+};
+
+mod cache;
+#[cfg(test)]
+mod memory;
+
+pub use cache::{listen_for_changes, CachedStore};
+
+#[cfg(test)]
+pub use memory::{board, row, MemoryStore};
+
+type StoreResult<T> = Result<T, sqlx::Error>;
+
+/// Everything the commands read or change about submissions, their leaderboards and the
+/// settings members make around them
+#[async_trait]
+pub trait LeaderboardStore: Send + Sync {
+    /// The leaderboard of the most recent valid submission of a game
+    async fn latest_game_leaderboard(&self, game_name: &str) -> StoreResult<Vec<LeaderboardRow>>;
+
+    /// Like [`LeaderboardStore::latest_game_leaderboard`], only the positions between `from` and
+    /// `to`, inclusive
+    async fn latest_game_leaderboard_between(
+        &self,
+        game_name: &str,
+        from: i64,
+        to: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>>;
+
+    /// Every leaderboard a player is on, looking only at the most recent valid submission per game
+    async fn latest_player_leaderboards(
+        &self,
+        player_name: &str,
+    ) -> StoreResult<Vec<LeaderboardRow>>;
+
+    /// Case-insensitive prefix search over the players of the most recent valid submissions
+    async fn player_names_starting_with(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> StoreResult<Vec<String>>;
+
+    /// The last valid submission per game a player was on
+    async fn player_last_seen(&self, player_name: &str) -> StoreResult<Vec<(String, i64)>>;
+
+    /// Every player that has been on any valid submission, older ones included
+    async fn all_player_names(&self) -> StoreResult<Vec<String>>;
+
+    /// Every game with at least one submission, whether it's registered or not
+    async fn submitted_game_names(&self) -> StoreResult<Vec<String>>;
+
+    /// Loads whatever is kept about a game again, for changes made around the store like adding
+    /// the game. Stores that don't keep anything have nothing to do
+    async fn refresh_game(&self, _game_name: &str) {}
+
+    /// A player's position on every valid submission of a game, newest first
+    async fn player_history(
+        &self,
+        game_name: &str,
+        player_name: &str,
+    ) -> StoreResult<Vec<HistoryRow>>;
+
+    /// Every submission of a game, valid or not, newest first
+    async fn game_submissions(&self, game_name: &str) -> StoreResult<Vec<Submission>>;
+
+    async fn submission_leaderboard(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>>;
+
+    /// Saves a submission under `submission_id`, or one past the newest ID of any game when that
+    /// isn't newer. Returns the ID it was saved under
+    async fn insert_submission(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+        submitter: u64,
+        rows: &[SubmissionRow],
+    ) -> StoreResult<i64>;
+
+    /// Returns the amount of submissions changed
+    async fn set_submission_validity(&self, submission_id: i64, valid: bool) -> StoreResult<u64>;
+
+    /// Removes a submission and its leaderboard rows, returns the amount of submissions removed
+    async fn delete_submission(&self, submission_id: i64) -> StoreResult<u64>;
+
+    /// Valid submissions that weren't announced yet, oldest first
+    async fn unannounced_submissions(&self) -> StoreResult<Vec<Submission>>;
+
+    async fn mark_submission_announced(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<()>;
+
+    /// Every submission with its amount of leaderboard rows, optionally only for one game, newest
+    /// first
+    async fn submission_summaries(
+        &self,
+        game_name: Option<&str>,
+    ) -> StoreResult<Vec<SubmissionSummary>>;
+
+    async fn submission_summary(
+        &self,
+        submission_id: i64,
+    ) -> StoreResult<Option<SubmissionSummary>>;
+
+    /// Saving under an existing key replaces that session
+    async fn save_paginator_session(&self, key: i64, session: &PaginatorSession)
+        -> StoreResult<()>;
+
+    /// Only sessions that haven't expired yet
+    async fn paginator_session(&self, key: i64) -> StoreResult<Option<PaginatorSession>>;
+
+    async fn delete_paginator_session(&self, key: i64) -> StoreResult<()>;
+
+    /// The player a Discord account is linked to
+    async fn linked_player(&self, discord_user_id: i64) -> StoreResult<Option<String>>;
+
+    /// A Discord account links to one player, linking again replaces it
+    async fn link_account(&self, discord_user_id: i64, player_name: &str) -> StoreResult<()>;
+
+    /// Returns the amount of links removed
+    async fn unlink_account(&self, discord_user_id: i64) -> StoreResult<u64>;
+
+    /// Following a player again, in any case, only changes the name and where the notifications go
+    async fn insert_follow(
+        &self,
+        discord_user_id: i64,
+        player_name: &str,
+        channel_id: Option<i64>,
+    ) -> StoreResult<()>;
+
+    /// Returns the amount of follows removed, names are matched regardless of case
+    async fn delete_follow(&self, discord_user_id: i64, player_name: &str) -> StoreResult<u64>;
+
+    /// Every player a Discord account follows, by name
+    async fn user_follows(&self, discord_user_id: i64) -> StoreResult<Vec<Follow>>;
+
+    /// Every follow of the given players, names are matched regardless of case
+    async fn followers_of(&self, player_names: &[String]) -> StoreResult<Vec<Follow>>;
+
+    /// The channel of every guild that announces new submissions of a game
+    async fn announcement_channels(&self, game_name: &str) -> StoreResult<Vec<i64>>;
+
+    /// A guild has at most one announcement channel per game
+    async fn set_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+        channel_id: i64,
+    ) -> StoreResult<()>;
+
+    /// Returns the amount of announcement channels removed
+    async fn delete_announcement_channel(&self, guild_id: i64, game_name: &str)
+        -> StoreResult<u64>;
+
+    async fn insert_audit_entry(&self, actor: i64, action: &str, target: &str) -> StoreResult<()>;
+
+    /// Every administrative action, newest first
+    async fn audit_entries(&self) -> StoreResult<Vec<AuditEntry>>;
+}
+
+pub struct PostgresStore {
+    db: Pool<Postgres>,
+}
+
+impl PostgresStore {
+    pub fn new(db: Pool<Postgres>) -> PostgresStore {
+        PostgresStore { db }
+    }
+}
+
+#[async_trait]
+impl LeaderboardStore for PostgresStore {
+    async fn latest_game_leaderboard(&self, game_name: &str) -> StoreResult<Vec<LeaderboardRow>> {
+        queries::latest_game_leaderboard(&self.db, game_name).await
+    }
+
+    async fn latest_game_leaderboard_between(
+        &self,
+        game_name: &str,
+        from: i64,
+        to: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        queries::latest_game_leaderboard_between(&self.db, game_name, from, to).await
+    }
+
+    async fn latest_player_leaderboards(
+        &self,
+        player_name: &str,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        queries::latest_player_leaderboards(&self.db, player_name).await
+    }
+
+    async fn player_names_starting_with(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> StoreResult<Vec<String>> {
+        queries::player_names_starting_with(&self.db, prefix, limit).await
+    }
+
+    async fn player_last_seen(&self, player_name: &str) -> StoreResult<Vec<(String, i64)>> {
+        queries::player_last_seen(&self.db, player_name).await
+    }
+
+    async fn all_player_names(&self) -> StoreResult<Vec<String>> {
+        queries::all_player_names(&self.db).await
+    }
+
+    async fn submitted_game_names(&self) -> StoreResult<Vec<String>> {
+        queries::submitted_game_names(&self.db).await
+    }
+
+    async fn player_history(
+        &self,
+        game_name: &str,
+        player_name: &str,
+    ) -> StoreResult<Vec<HistoryRow>> {
+        queries::player_history(&self.db, game_name, player_name).await
+    }
+
+    async fn game_submissions(&self, game_name: &str) -> StoreResult<Vec<Submission>> {
+        queries::game_submissions(&self.db, game_name).await
+    }
+
+    async fn submission_leaderboard(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        queries::submission_leaderboard(&self.db, game_name, submission_id).await
+    }
+
+    async fn insert_submission(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+        submitter: u64,
+        rows: &[SubmissionRow],
+    ) -> StoreResult<i64> {
+        queries::insert_submission(&self.db, game_name, submission_id, submitter, rows).await
+    }
+
+    async fn set_submission_validity(&self, submission_id: i64, valid: bool) -> StoreResult<u64> {
+        queries::set_submission_validity(&self.db, submission_id, valid).await
+    }
+
+    async fn delete_submission(&self, submission_id: i64) -> StoreResult<u64> {
+        queries::delete_submission(&self.db, submission_id).await
+    }
+
+    async fn unannounced_submissions(&self) -> StoreResult<Vec<Submission>> {
+        queries::unannounced_submissions(&self.db).await
+    }
+
+    async fn mark_submission_announced(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<()> {
+        queries::mark_submission_announced(&self.db, game_name, submission_id).await
+    }
+
+    async fn submission_summaries(
+        &self,
+        game_name: Option<&str>,
+    ) -> StoreResult<Vec<SubmissionSummary>> {
+        queries::submission_summaries(&self.db, game_name).await
+    }
+
+    async fn submission_summary(
+        &self,
+        submission_id: i64,
+    ) -> StoreResult<Option<SubmissionSummary>> {
+        queries::submission_summary(&self.db, submission_id).await
+    }
+
+    async fn save_paginator_session(
+        &self,
+        key: i64,
+        session: &PaginatorSession,
+    ) -> StoreResult<()> {
+        queries::insert_paginator_session(&self.db, key, session).await
+    }
+
+    async fn paginator_session(&self, key: i64) -> StoreResult<Option<PaginatorSession>> {
+        queries::paginator_session(&self.db, key).await
+    }
+
+    async fn delete_paginator_session(&self, key: i64) -> StoreResult<()> {
+        queries::delete_paginator_session(&self.db, key).await
+    }
+
+    async fn linked_player(&self, discord_user_id: i64) -> StoreResult<Option<String>> {
+        queries::linked_player(&self.db, discord_user_id).await
+    }
+
+    async fn link_account(&self, discord_user_id: i64, player_name: &str) -> StoreResult<()> {
+        queries::link_account(&self.db, discord_user_id, player_name).await
+    }
+
+    async fn unlink_account(&self, discord_user_id: i64) -> StoreResult<u64> {
+        queries::unlink_account(&self.db, discord_user_id).await
+    }
+
+    async fn insert_follow(
+        &self,
+        discord_user_id: i64,
+        player_name: &str,
+        channel_id: Option<i64>,
+    ) -> StoreResult<()> {
+        queries::insert_follow(&self.db, discord_user_id, player_name, channel_id).await
+    }
+
+    async fn delete_follow(&self, discord_user_id: i64, player_name: &str) -> StoreResult<u64> {
+        queries::delete_follow(&self.db, discord_user_id, player_name).await
+    }
+
+    async fn user_follows(&self, discord_user_id: i64) -> StoreResult<Vec<Follow>> {
+        queries::user_follows(&self.db, discord_user_id).await
+    }
+
+    async fn followers_of(&self, player_names: &[String]) -> StoreResult<Vec<Follow>> {
+        queries::followers_of(&self.db, player_names).await
+    }
+
+    async fn announcement_channels(&self, game_name: &str) -> StoreResult<Vec<i64>> {
+        queries::announcement_channels(&self.db, game_name).await
+    }
+
+    async fn set_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+        channel_id: i64,
+    ) -> StoreResult<()> {
+        queries::set_announcement_channel(&self.db, guild_id, game_name, channel_id).await
+    }
+
+    async fn delete_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+    ) -> StoreResult<u64> {
+        queries::delete_announcement_channel(&self.db, guild_id, game_name).await
+    }
+
+    async fn insert_audit_entry(&self, actor: i64, action: &str, target: &str) -> StoreResult<()> {
+        queries::insert_audit_entry(&self.db, actor, action, target).await
+    }
+
+    async fn audit_entries(&self) -> StoreResult<Vec<AuditEntry>> {
+        queries::audit_entries(&self.db).await
+    }
+}
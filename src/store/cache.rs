@@ -0,0 +1,533 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use serenity::async_trait;
+use sqlx::{postgres::PgListener, Pool, Postgres};
+use tokio::time::sleep;
+
+use super::{LeaderboardStore, StoreResult};
+use crate::{
+    commands::{HistoryRow, LeaderboardRow},
+    paginator::PaginatorSession,
+    queries::{AuditEntry, Follow, Submission, SubmissionSummary},
+    submission::SubmissionRow,
+};
+
+/// The trigger on `submissions` notifies this channel with the name of the game that changed
+pub const SUBMISSIONS_CHANNEL: &str = "submissions_changed";
+
+/// How long to wait before listening again after losing the connection
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct Latest {
+    /// The leaderboard of the most recent valid submission per game
+    boards: BTreeMap<String, Vec<LeaderboardRow>>,
+    /// Every row of `boards` per player
+    players: BTreeMap<String, Vec<LeaderboardRow>>,
+    /// Games whose leaderboard couldn't be loaded, player lookups go to the database while there
+    /// are any
+    stale: HashSet<String>,
+    /// Whether every game with submissions is in `boards` or `stale`, player lookups go to the
+    /// database until the games could be listed
+    all_games: bool,
+}
+
+impl Latest {
+    /// Whether player lookups can be answered from the cached leaderboards alone
+    fn is_complete(&self) -> bool {
+        self.all_games && self.stale.is_empty()
+    }
+
+    fn set_board(&mut self, game_name: &str, board: Option<Vec<LeaderboardRow>>) {
+        match board {
+            Some(board) => {
+                self.stale.remove(game_name);
+                self.boards.insert(game_name.to_owned(), board);
+            }
+            None => {
+                self.stale.insert(game_name.to_owned());
+                self.boards.remove(game_name);
+            }
+        }
+
+        self.players.clear();
+        for row in self.boards.values().flatten() {
+            self.players
+                .entry(row.player.clone())
+                .or_default()
+                .push(row.clone());
+        }
+        for rows in self.players.values_mut() {
+            rows.sort_by_key(|row| row.position);
+        }
+    }
+}
+
+/// Serves the latest leaderboards from memory and passes everything else on to `inner`. Changes
+/// made through the cache refresh it right away, [`listen_for_changes`] picks up the ones made elsewhere
+pub struct CachedStore<S> {
+    inner: S,
+    latest: Mutex<Latest>,
+    /// Refreshes run one at a time, so an older leaderboard can't replace a newer one
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+impl<S: LeaderboardStore> CachedStore<S> {
+    /// Caches the latest leaderboard of every game with submissions, registered or not
+    pub async fn load(inner: S) -> CachedStore<S> {
+        let cache = CachedStore {
+            inner,
+            latest: Mutex::new(Latest::default()),
+            refreshing: tokio::sync::Mutex::new(()),
+        };
+        cache.refresh_all().await;
+        cache
+    }
+
+    fn latest(&self) -> MutexGuard<'_, Latest> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Loads the latest leaderboard of a game again
+    pub async fn refresh(&self, game_name: &str) {
+        let _refreshing = self.refreshing.lock().await;
+        let board = match self.inner.latest_game_leaderboard(game_name).await {
+            Ok(board) => Some(board),
+            Err(why) => {
+                println!("Cannot cache the {} leaderboard: {}", game_name, why);
+                None
+            }
+        };
+        self.latest().set_board(game_name, board);
+    }
+
+    /// Loads every cached leaderboard again, along with those of games that got submissions since
+    pub async fn refresh_all(&self) {
+        let submitted = match self.inner.submitted_game_names().await {
+            Ok(game_names) => Some(game_names),
+            Err(why) => {
+                println!("Cannot list the games to cache: {}", why);
+                None
+            }
+        };
+        let mut game_names: BTreeSet<String> = {
+            let latest = self.latest();
+            latest
+                .boards
+                .keys()
+                .chain(latest.stale.iter())
+                .cloned()
+                .collect()
+        };
+        game_names.extend(submitted.iter().flatten().cloned());
+
+        for game_name in game_names {
+            self.refresh(&game_name).await;
+        }
+        self.latest().all_games = submitted.is_some();
+    }
+
+    async fn board(&self, game_name: &str) -> StoreResult<Vec<LeaderboardRow>> {
+        let cached = self.latest().boards.get(game_name).cloned();
+        if let Some(board) = cached {
+            return Ok(board);
+        }
+        self.refresh(game_name).await;
+        let refreshed = self.latest().boards.get(game_name).cloned();
+        match refreshed {
+            Some(board) => Ok(board),
+            // Couldn't be loaded, the error is worth passing on
+            None => self.inner.latest_game_leaderboard(game_name).await,
+        }
+    }
+}
+
+/// Keeps the cache in line with the trigger on `submissions`, for changes made by the API or by
+/// hand as well
+pub async fn listen_for_changes<S: LeaderboardStore>(
+    db: Pool<Postgres>,
+    cache: Arc<CachedStore<S>>,
+) {
+    loop {
+        let mut listener = match PgListener::connect_with(&db).await {
+            Ok(listener) => listener,
+            Err(why) => {
+                println!("Cannot listen for submission changes: {}", why);
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        if let Err(why) = listener.listen(SUBMISSIONS_CHANNEL).await {
+            println!("Cannot listen for submission changes: {}", why);
+            sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+        // Anything could have changed while nobody was listening
+        cache.refresh_all().await;
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => cache.refresh(notification.payload()).await,
+                // The connection dropped and notifications sent until it's back are lost
+                Ok(None) => cache.refresh_all().await,
+                Err(why) => {
+                    println!("Stopped listening for submission changes: {}", why);
+                    break;
+                }
+            }
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[async_trait]
+impl<S: LeaderboardStore> LeaderboardStore for CachedStore<S> {
+    async fn latest_game_leaderboard(&self, game_name: &str) -> StoreResult<Vec<LeaderboardRow>> {
+        self.board(game_name).await
+    }
+
+    async fn latest_game_leaderboard_between(
+        &self,
+        game_name: &str,
+        from: i64,
+        to: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        let mut board = self.board(game_name).await?;
+        board.retain(|row| (from..=to).contains(&(row.position as i64)));
+        Ok(board)
+    }
+
+    async fn latest_player_leaderboards(
+        &self,
+        player_name: &str,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        {
+            let latest = self.latest();
+            if latest.is_complete() {
+                return Ok(latest.players.get(player_name).cloned().unwrap_or_default());
+            }
+        }
+        self.inner.latest_player_leaderboards(player_name).await
+    }
+
+    async fn player_names_starting_with(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> StoreResult<Vec<String>> {
+        {
+            let latest = self.latest();
+            if latest.is_complete() {
+                let prefix = prefix.to_lowercase();
+                return Ok(latest
+                    .players
+                    .keys()
+                    .filter(|name| name.to_lowercase().starts_with(&prefix))
+                    .take(limit.max(0) as usize)
+                    .cloned()
+                    .collect());
+            }
+        }
+        self.inner.player_names_starting_with(prefix, limit).await
+    }
+
+    async fn player_last_seen(&self, player_name: &str) -> StoreResult<Vec<(String, i64)>> {
+        self.inner.player_last_seen(player_name).await
+    }
+
+    /// Older submissions aren't cached, so this always goes to `inner`
+    async fn all_player_names(&self) -> StoreResult<Vec<String>> {
+        self.inner.all_player_names().await
+    }
+
+    async fn submitted_game_names(&self) -> StoreResult<Vec<String>> {
+        self.inner.submitted_game_names().await
+    }
+
+    async fn refresh_game(&self, game_name: &str) {
+        self.refresh(game_name).await;
+    }
+
+    async fn player_history(
+        &self,
+        game_name: &str,
+        player_name: &str,
+    ) -> StoreResult<Vec<HistoryRow>> {
+        self.inner.player_history(game_name, player_name).await
+    }
+
+    async fn game_submissions(&self, game_name: &str) -> StoreResult<Vec<Submission>> {
+        self.inner.game_submissions(game_name).await
+    }
+
+    async fn submission_leaderboard(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        self.inner
+            .submission_leaderboard(game_name, submission_id)
+            .await
+    }
+
+    async fn insert_submission(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+        submitter: u64,
+        rows: &[SubmissionRow],
+    ) -> StoreResult<i64> {
+        let submission_id = self
+            .inner
+            .insert_submission(game_name, submission_id, submitter, rows)
+            .await?;
+        self.refresh(game_name).await;
+        Ok(submission_id)
+    }
+
+    /// The game of the submission isn't known here, so every leaderboard is refreshed
+    async fn set_submission_validity(&self, submission_id: i64, valid: bool) -> StoreResult<u64> {
+        let changed = self
+            .inner
+            .set_submission_validity(submission_id, valid)
+            .await?;
+        if changed > 0 {
+            self.refresh_all().await;
+        }
+        Ok(changed)
+    }
+
+    /// The game of the submission isn't known here, so every leaderboard is refreshed
+    async fn delete_submission(&self, submission_id: i64) -> StoreResult<u64> {
+        let removed = self.inner.delete_submission(submission_id).await?;
+        if removed > 0 {
+            self.refresh_all().await;
+        }
+        Ok(removed)
+    }
+
+    async fn unannounced_submissions(&self) -> StoreResult<Vec<Submission>> {
+        self.inner.unannounced_submissions().await
+    }
+
+    async fn mark_submission_announced(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<()> {
+        self.inner
+            .mark_submission_announced(game_name, submission_id)
+            .await
+    }
+
+    async fn submission_summaries(
+        &self,
+        game_name: Option<&str>,
+    ) -> StoreResult<Vec<SubmissionSummary>> {
+        self.inner.submission_summaries(game_name).await
+    }
+
+    async fn submission_summary(
+        &self,
+        submission_id: i64,
+    ) -> StoreResult<Option<SubmissionSummary>> {
+        self.inner.submission_summary(submission_id).await
+    }
+
+    async fn save_paginator_session(
+        &self,
+        key: i64,
+        session: &PaginatorSession,
+    ) -> StoreResult<()> {
+        self.inner.save_paginator_session(key, session).await
+    }
+
+    async fn paginator_session(&self, key: i64) -> StoreResult<Option<PaginatorSession>> {
+        self.inner.paginator_session(key).await
+    }
+
+    async fn delete_paginator_session(&self, key: i64) -> StoreResult<()> {
+        self.inner.delete_paginator_session(key).await
+    }
+
+    async fn linked_player(&self, discord_user_id: i64) -> StoreResult<Option<String>> {
+        self.inner.linked_player(discord_user_id).await
+    }
+
+    async fn link_account(&self, discord_user_id: i64, player_name: &str) -> StoreResult<()> {
+        self.inner.link_account(discord_user_id, player_name).await
+    }
+
+    async fn unlink_account(&self, discord_user_id: i64) -> StoreResult<u64> {
+        self.inner.unlink_account(discord_user_id).await
+    }
+
+    async fn insert_follow(
+        &self,
+        discord_user_id: i64,
+        player_name: &str,
+        channel_id: Option<i64>,
+    ) -> StoreResult<()> {
+        self.inner
+            .insert_follow(discord_user_id, player_name, channel_id)
+            .await
+    }
+
+    async fn delete_follow(&self, discord_user_id: i64, player_name: &str) -> StoreResult<u64> {
+        self.inner.delete_follow(discord_user_id, player_name).await
+    }
+
+    async fn user_follows(&self, discord_user_id: i64) -> StoreResult<Vec<Follow>> {
+        self.inner.user_follows(discord_user_id).await
+    }
+
+    async fn followers_of(&self, player_names: &[String]) -> StoreResult<Vec<Follow>> {
+        self.inner.followers_of(player_names).await
+    }
+
+    async fn announcement_channels(&self, game_name: &str) -> StoreResult<Vec<i64>> {
+        self.inner.announcement_channels(game_name).await
+    }
+
+    async fn set_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+        channel_id: i64,
+    ) -> StoreResult<()> {
+        self.inner
+            .set_announcement_channel(guild_id, game_name, channel_id)
+            .await
+    }
+
+    async fn delete_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+    ) -> StoreResult<u64> {
+        self.inner
+            .delete_announcement_channel(guild_id, game_name)
+            .await
+    }
+
+    async fn insert_audit_entry(&self, actor: i64, action: &str, target: &str) -> StoreResult<()> {
+        self.inner.insert_audit_entry(actor, action, target).await
+    }
+
+    async fn audit_entries(&self) -> StoreResult<Vec<AuditEntry>> {
+        self.inner.audit_entries().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{board, row, MemoryStore};
+
+    const TEW: &str = "Team EggWars";
+    const FFA: &str = "Free For All";
+
+    #[tokio::test]
+    async fn follows_changes_made_through_it() {
+        let store = MemoryStore::with_submissions(vec![
+            (TEW, 100, board(3, 0)),
+            (FFA, 101, vec![row("Fesa", 2, 40)]),
+        ])
+        .await;
+        let cache = CachedStore::load(store).await;
+
+        assert_eq!(cache.latest_game_leaderboard(TEW).await.unwrap().len(), 3);
+        assert_eq!(
+            cache
+                .latest_player_leaderboards("Fesa")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        cache
+            .insert_submission(TEW, 200, 1, &[row("Fesa", 1, 50)])
+            .await
+            .unwrap();
+        let fesa = cache.latest_player_leaderboards("Fesa").await.unwrap();
+        assert_eq!(fesa.len(), 2);
+        assert_eq!((fesa[0].game.as_str(), fesa[0].position), (TEW, 1));
+        assert!(cache
+            .latest_player_leaderboards("player1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(cache.set_submission_validity(200, false).await.unwrap(), 1);
+        let latest = cache.latest_game_leaderboard(TEW).await.unwrap();
+        assert!(latest.iter().all(|row| row.unix_time_stamp == 100));
+        assert_eq!(
+            cache.player_names_starting_with("PLAY", 2).await.unwrap(),
+            vec![String::from("player1"), String::from("player2")]
+        );
+    }
+
+    #[tokio::test]
+    async fn picks_up_outside_changes_when_refreshed() {
+        let cache =
+            CachedStore::load(MemoryStore::with_submissions(vec![(TEW, 100, board(3, 0))]).await)
+                .await;
+
+        // Skips the cache, like a change made by another process would
+        cache
+            .inner
+            .insert_submission(TEW, 200, 1, &board(5, 0))
+            .await
+            .unwrap();
+        assert_eq!(cache.latest_game_leaderboard(TEW).await.unwrap().len(), 3);
+
+        cache.refresh(TEW).await;
+        assert_eq!(cache.latest_game_leaderboard(TEW).await.unwrap().len(), 5);
+        assert_eq!(
+            cache
+                .latest_game_leaderboard_between(TEW, 2, 3)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn covers_every_game_with_submissions() {
+        let cache = CachedStore::load(
+            MemoryStore::with_submissions(vec![
+                (TEW, 100, vec![row("Fesa", 1, 50)]),
+                ("Unregistered", 101, vec![row("Fesa", 3, 10)]),
+            ])
+            .await,
+        )
+        .await;
+
+        let fesa = cache.latest_player_leaderboards("Fesa").await.unwrap();
+        assert_eq!(fesa.len(), 2);
+        assert_eq!(fesa[1].game, "Unregistered");
+
+        cache
+            .inner
+            .insert_submission(FFA, 200, 1, &[row("Fesa", 2, 40)])
+            .await
+            .unwrap();
+        cache.refresh_game(FFA).await;
+        assert_eq!(
+            cache
+                .latest_player_leaderboards("Fesa")
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+use chrono::Utc;
+use serenity::async_trait;
+
+use super::{LeaderboardStore, StoreResult};
+use crate::{
+    commands::{HistoryRow, LeaderboardRow},
+    paginator::PaginatorSession,
+    queries::{AuditEntry, Follow, Submission, SubmissionSummary},
+    submission::SubmissionRow,
+};
+
+/// Keeps everything in memory, so the commands can be exercised without a database
+#[derive(Default)]
+pub struct MemoryStore {
+    submissions: Mutex<Vec<Submission>>,
+    rows: Mutex<Vec<LeaderboardRow>>,
+    paginator_sessions: Mutex<HashMap<i64, PaginatorSession>>,
+    /// Submitter per submission ID
+    submitters: Mutex<HashMap<i64, u64>>,
+    linked_accounts: Mutex<HashMap<i64, String>>,
+    follows: Mutex<Vec<Follow>>,
+    /// Channel per guild and game
+    announcement_channels: Mutex<HashMap<(i64, String), i64>>,
+    audit_log: Mutex<Vec<AuditEntry>>,
+    /// IDs of the submissions that were announced
+    announced: Mutex<BTreeSet<i64>>,
+}
+
+impl MemoryStore {
+    /// The most recent valid submission per game
+    fn latest_valid(&self) -> BTreeMap<String, i64> {
+        let mut latest = BTreeMap::new();
+        for submission in self.submissions.lock().unwrap().iter() {
+            if !submission.valid {
+                continue;
+            }
+            let id = latest.entry(submission.game.clone()).or_insert(i64::MIN);
+            *id = (*id).max(submission.unix_time_stamp);
+        }
+        latest
+    }
+
+    fn is_valid(&self, game_name: &str, submission_id: i64) -> bool {
+        self.submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|s| s.valid && s.game == game_name && s.unix_time_stamp == submission_id)
+    }
+
+    fn summarize(&self, submission: Submission) -> SubmissionSummary {
+        let row_count = self
+            .rows_where(|row| {
+                row.game == submission.game && row.unix_time_stamp == submission.unix_time_stamp
+            })
+            .len() as i64;
+        SubmissionSummary {
+            submitter: self
+                .submitters
+                .lock()
+                .unwrap()
+                .get(&submission.unix_time_stamp)
+                .map(|submitter| *submitter as i64),
+            game: submission.game,
+            unix_time_stamp: submission.unix_time_stamp,
+            valid: submission.valid,
+            row_count,
+        }
+    }
+
+    /// A store holding the given `(game, submission ID, rows)` submissions
+    pub async fn with_submissions(submissions: Vec<(&str, i64, Vec<SubmissionRow>)>) -> Self {
+        let store = Self::default();
+        for (game, id, rows) in submissions {
+            store.insert_submission(game, id, 1, &rows).await.unwrap();
+        }
+        store
+    }
+
+    fn rows_where(&self, filter: impl Fn(&LeaderboardRow) -> bool) -> Vec<LeaderboardRow> {
+        let mut rows: Vec<LeaderboardRow> = self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|row| filter(row))
+            .cloned()
+            .collect();
+        rows.sort_by(|a, b| a.game.cmp(&b.game).then(a.position.cmp(&b.position)));
+        rows
+    }
+}
+
+/// Names are `player1`, `player2`, ... with decreasing scores
+pub fn board(size: i32, score_offset: i32) -> Vec<SubmissionRow> {
+    (1..=size)
+        .map(|position| SubmissionRow {
+            player: format!("player{}", position),
+            position,
+            score: 1000 - position + score_offset,
+        })
+        .collect()
+}
+
+pub fn row(player: &str, position: i32, score: i32) -> SubmissionRow {
+    SubmissionRow {
+        player: String::from(player),
+        position,
+        score,
+    }
+}
+
+#[async_trait]
+impl LeaderboardStore for MemoryStore {
+    async fn latest_game_leaderboard(&self, game_name: &str) -> StoreResult<Vec<LeaderboardRow>> {
+        let Some(latest) = self.latest_valid().get(game_name).copied() else {
+            return Ok(vec![]);
+        };
+        Ok(self.rows_where(|row| row.game == game_name && row.unix_time_stamp == latest))
+    }
+
+    async fn latest_game_leaderboard_between(
+        &self,
+        game_name: &str,
+        from: i64,
+        to: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        let mut rows = self.latest_game_leaderboard(game_name).await?;
+        rows.retain(|row| (from..=to).contains(&(row.position as i64)));
+        Ok(rows)
+    }
+
+    async fn latest_player_leaderboards(
+        &self,
+        player_name: &str,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        let latest = self.latest_valid();
+        Ok(self.rows_where(|row| {
+            row.player == player_name && latest.get(&row.game) == Some(&row.unix_time_stamp)
+        }))
+    }
+
+    async fn player_names_starting_with(
+        &self,
+        prefix: &str,
+        limit: i64,
+    ) -> StoreResult<Vec<String>> {
+        let latest = self.latest_valid();
+        let prefix = prefix.to_lowercase();
+        let names: BTreeSet<String> = self
+            .rows_where(|row| {
+                latest.get(&row.game) == Some(&row.unix_time_stamp)
+                    && row.player.to_lowercase().starts_with(&prefix)
+            })
+            .into_iter()
+            .map(|row| row.player)
+            .collect();
+        Ok(names.into_iter().take(limit as usize).collect())
+    }
+
+    async fn player_last_seen(&self, player_name: &str) -> StoreResult<Vec<(String, i64)>> {
+        let mut last_seen: BTreeMap<String, i64> = BTreeMap::new();
+        for row in self.rows_where(|row| row.player == player_name) {
+            if self.is_valid(&row.game, row.unix_time_stamp) {
+                let id = last_seen.entry(row.game).or_insert(i64::MIN);
+                *id = (*id).max(row.unix_time_stamp);
+            }
+        }
+        Ok(last_seen.into_iter().collect())
+    }
+
+    async fn all_player_names(&self) -> StoreResult<Vec<String>> {
+        let names: BTreeSet<String> = self
+            .rows_where(|row| self.is_valid(&row.game, row.unix_time_stamp))
+            .into_iter()
+            .map(|row| row.player)
+            .collect();
+        Ok(names.into_iter().collect())
+    }
+
+    async fn submitted_game_names(&self) -> StoreResult<Vec<String>> {
+        let names: BTreeSet<String> = self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|submission| submission.game.clone())
+            .collect();
+        Ok(names.into_iter().collect())
+    }
+
+    async fn player_history(
+        &self,
+        game_name: &str,
+        player_name: &str,
+    ) -> StoreResult<Vec<HistoryRow>> {
+        let submissions = self.game_submissions(game_name).await?;
+        Ok(submissions
+            .into_iter()
+            .filter(|submission| submission.valid)
+            .map(|submission| {
+                let row = self
+                    .rows_where(|row| {
+                        row.game == game_name
+                            && row.unix_time_stamp == submission.unix_time_stamp
+                            && row.player == player_name
+                    })
+                    .pop();
+                HistoryRow {
+                    unix_time_stamp: submission.unix_time_stamp,
+                    position: row.as_ref().map(|row| row.position),
+                    score: row.as_ref().map(|row| row.score),
+                }
+            })
+            .collect())
+    }
+
+    async fn game_submissions(&self, game_name: &str) -> StoreResult<Vec<Submission>> {
+        let mut submissions: Vec<Submission> = self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|submission| submission.game == game_name)
+            .cloned()
+            .collect();
+        submissions.sort_by_key(|submission| -submission.unix_time_stamp);
+        Ok(submissions)
+    }
+
+    async fn submission_leaderboard(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<Vec<LeaderboardRow>> {
+        Ok(self.rows_where(|row| row.game == game_name && row.unix_time_stamp == submission_id))
+    }
+
+    async fn insert_submission(
+        &self,
+        game_name: &str,
+        submission_id: i64,
+        submitter: u64,
+        rows: &[SubmissionRow],
+    ) -> StoreResult<i64> {
+        let submission_id = {
+            let mut submissions = self.submissions.lock().unwrap();
+            let newest = submissions.iter().map(|s| s.unix_time_stamp).max();
+            let submission_id =
+                newest.map_or(submission_id, |newest| submission_id.max(newest + 1));
+            submissions.push(Submission {
+                game: game_name.to_owned(),
+                unix_time_stamp: submission_id,
+                valid: true,
+            });
+            submission_id
+        };
+        self.rows
+            .lock()
+            .unwrap()
+            .extend(rows.iter().map(|row| LeaderboardRow {
+                player: row.player.clone(),
+                position: row.position,
+                score: row.score,
+                game: game_name.to_owned(),
+                unix_time_stamp: submission_id,
+            }));
+        self.submitters
+            .lock()
+            .unwrap()
+            .insert(submission_id, submitter);
+        Ok(submission_id)
+    }
+
+    async fn set_submission_validity(&self, submission_id: i64, valid: bool) -> StoreResult<u64> {
+        let mut affected = 0;
+        for submission in self.submissions.lock().unwrap().iter_mut() {
+            if submission.unix_time_stamp == submission_id {
+                submission.valid = valid;
+                affected += 1;
+            }
+        }
+        Ok(affected)
+    }
+
+    async fn delete_submission(&self, submission_id: i64) -> StoreResult<u64> {
+        let removed = {
+            let mut submissions = self.submissions.lock().unwrap();
+            let before = submissions.len();
+            submissions.retain(|submission| submission.unix_time_stamp != submission_id);
+            before - submissions.len()
+        };
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|row| row.unix_time_stamp != submission_id);
+        Ok(removed as u64)
+    }
+
+    async fn unannounced_submissions(&self) -> StoreResult<Vec<Submission>> {
+        let announced = self.announced.lock().unwrap();
+        let mut submissions: Vec<Submission> = self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.valid && !announced.contains(&s.unix_time_stamp))
+            .cloned()
+            .collect();
+        submissions.sort_by_key(|s| s.unix_time_stamp);
+        Ok(submissions)
+    }
+
+    async fn mark_submission_announced(
+        &self,
+        _game_name: &str,
+        submission_id: i64,
+    ) -> StoreResult<()> {
+        self.announced.lock().unwrap().insert(submission_id);
+        Ok(())
+    }
+
+    async fn submission_summaries(
+        &self,
+        game_name: Option<&str>,
+    ) -> StoreResult<Vec<SubmissionSummary>> {
+        let submissions: Vec<Submission> = self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| game_name.is_none_or(|game_name| s.game == game_name))
+            .cloned()
+            .collect();
+        let mut summaries: Vec<SubmissionSummary> = submissions
+            .into_iter()
+            .map(|submission| self.summarize(submission))
+            .collect();
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.unix_time_stamp));
+        Ok(summaries)
+    }
+
+    async fn submission_summary(
+        &self,
+        submission_id: i64,
+    ) -> StoreResult<Option<SubmissionSummary>> {
+        let submission = self
+            .submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.unix_time_stamp == submission_id)
+            .cloned();
+        Ok(submission.map(|submission| self.summarize(submission)))
+    }
+
+    async fn save_paginator_session(
+        &self,
+        key: i64,
+        session: &PaginatorSession,
+    ) -> StoreResult<()> {
+        self.paginator_sessions
+            .lock()
+            .unwrap()
+            .insert(key, session.clone());
+        Ok(())
+    }
+
+    async fn paginator_session(&self, key: i64) -> StoreResult<Option<PaginatorSession>> {
+        Ok(self
+            .paginator_sessions
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|session| !session.is_expired())
+            .cloned())
+    }
+
+    async fn delete_paginator_session(&self, key: i64) -> StoreResult<()> {
+        self.paginator_sessions.lock().unwrap().remove(&key);
+        Ok(())
+    }
+
+    async fn linked_player(&self, discord_user_id: i64) -> StoreResult<Option<String>> {
+        Ok(self
+            .linked_accounts
+            .lock()
+            .unwrap()
+            .get(&discord_user_id)
+            .cloned())
+    }
+
+    async fn link_account(&self, discord_user_id: i64, player_name: &str) -> StoreResult<()> {
+        self.linked_accounts
+            .lock()
+            .unwrap()
+            .insert(discord_user_id, player_name.to_owned());
+        Ok(())
+    }
+
+    async fn unlink_account(&self, discord_user_id: i64) -> StoreResult<u64> {
+        let removed = self
+            .linked_accounts
+            .lock()
+            .unwrap()
+            .remove(&discord_user_id);
+        Ok(removed.is_some() as u64)
+    }
+
+    async fn insert_follow(
+        &self,
+        discord_user_id: i64,
+        player_name: &str,
+        channel_id: Option<i64>,
+    ) -> StoreResult<()> {
+        self.delete_follow(discord_user_id, player_name).await?;
+        self.follows.lock().unwrap().push(Follow {
+            discord_user_id,
+            player: player_name.to_owned(),
+            channel_id,
+        });
+        Ok(())
+    }
+
+    async fn delete_follow(&self, discord_user_id: i64, player_name: &str) -> StoreResult<u64> {
+        let mut follows = self.follows.lock().unwrap();
+        let before = follows.len();
+        follows.retain(|follow| {
+            follow.discord_user_id != discord_user_id
+                || follow.player.to_lowercase() != player_name.to_lowercase()
+        });
+        Ok((before - follows.len()) as u64)
+    }
+
+    async fn user_follows(&self, discord_user_id: i64) -> StoreResult<Vec<Follow>> {
+        let mut follows: Vec<Follow> = self
+            .follows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|follow| follow.discord_user_id == discord_user_id)
+            .cloned()
+            .collect();
+        follows.sort_by(|a, b| a.player.cmp(&b.player));
+        Ok(follows)
+    }
+
+    async fn followers_of(&self, player_names: &[String]) -> StoreResult<Vec<Follow>> {
+        let player_names: Vec<String> = player_names
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        Ok(self
+            .follows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|follow| player_names.contains(&follow.player.to_lowercase()))
+            .cloned()
+            .collect())
+    }
+
+    async fn announcement_channels(&self, game_name: &str) -> StoreResult<Vec<i64>> {
+        Ok(self
+            .announcement_channels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((_, game), _)| game == game_name)
+            .map(|(_, channel_id)| *channel_id)
+            .collect())
+    }
+
+    async fn set_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+        channel_id: i64,
+    ) -> StoreResult<()> {
+        self.announcement_channels
+            .lock()
+            .unwrap()
+            .insert((guild_id, game_name.to_owned()), channel_id);
+        Ok(())
+    }
+
+    async fn delete_announcement_channel(
+        &self,
+        guild_id: i64,
+        game_name: &str,
+    ) -> StoreResult<u64> {
+        let removed = self
+            .announcement_channels
+            .lock()
+            .unwrap()
+            .remove(&(guild_id, game_name.to_owned()));
+        Ok(removed.is_some() as u64)
+    }
+
+    async fn insert_audit_entry(&self, actor: i64, action: &str, target: &str) -> StoreResult<()> {
+        self.audit_log.lock().unwrap().push(AuditEntry {
+            actor,
+            action: action.to_owned(),
+            target: target.to_owned(),
+            created_at: Utc::now().timestamp(),
+        });
+        Ok(())
+    }
+
+    async fn audit_entries(&self) -> StoreResult<Vec<AuditEntry>> {
+        let mut entries = self.audit_log.lock().unwrap().clone();
+        entries.reverse();
+        Ok(entries)
+    }
+}
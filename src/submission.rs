@@ -0,0 +1,201 @@
+use serde::Deserialize;
+
+/// The header a CSV file may start with
+const CSV_HEADER: &str = "player,position,score";
+
+/// The most rows a single submission may have
+const MAX_ROWS: usize = 1000;
+
+#[derive(Deserialize)]
+pub struct SubmissionRow {
+    pub player: String,
+    pub position: i32,
+    pub score: i32,
+}
+
+pub fn parse_submission(
+    filename: &str,
+    content: &[u8],
+    descending: bool,
+) -> Result<Vec<SubmissionRow>, String> {
+    let text = std::str::from_utf8(content)
+        .map_err(|_| String::from("The attached file isn't valid UTF-8 text."))?;
+
+    let mut rows = if filename.to_lowercase().ends_with(".json") {
+        parse_json(text)?
+    } else if filename.to_lowercase().ends_with(".csv") {
+        parse_csv(text)?
+    } else {
+        return Err(String::from(
+            "Only `.csv` and `.json` files can be submitted.",
+        ));
+    };
+
+    validate(&mut rows, descending)?;
+    Ok(rows)
+}
+
+fn parse_json(text: &str) -> Result<Vec<SubmissionRow>, String> {
+    serde_json::from_str::<Vec<SubmissionRow>>(text)
+        .map_err(|err| format!("Couldn't read the JSON file: {}", err))
+}
+
+fn parse_csv(text: &str) -> Result<Vec<SubmissionRow>, String> {
+    let mut rows = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if i == 0 && line.replace(' ', "").eq_ignore_ascii_case(CSV_HEADER) {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        let [player, position, score] = fields[..] else {
+            return Err(format!(
+                "Line {} should have exactly 3 columns: player,position,score",
+                i + 1
+            ));
+        };
+        let Ok(position) = position.parse::<i32>() else {
+            return Err(format!(
+                "Line {} has an invalid position: `{}`",
+                i + 1,
+                position
+            ));
+        };
+        let Ok(score) = score.parse::<i32>() else {
+            return Err(format!("Line {} has an invalid score: `{}`", i + 1, score));
+        };
+        rows.push(SubmissionRow {
+            player: player.to_owned(),
+            position,
+            score,
+        });
+    }
+    Ok(rows)
+}
+
+fn validate(rows: &mut [SubmissionRow], descending: bool) -> Result<(), String> {
+    if rows.is_empty() {
+        return Err(String::from("The attached file doesn't contain any rows."));
+    }
+    if rows.len() > MAX_ROWS {
+        return Err(format!(
+            "A submission can't have more than {} rows, this one has {}.",
+            MAX_ROWS,
+            rows.len()
+        ));
+    }
+
+    let mut positions: Vec<i32> = rows.iter().map(|row| row.position).collect();
+    positions.sort_unstable();
+    positions.dedup();
+    if positions.len() != rows.len() {
+        return Err(String::from("Every position may only appear once."));
+    }
+
+    for row in rows.iter() {
+        if row.player.len() < 2
+            || row.player.len() > 16
+            || !row
+                .player
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("`{}` isn't a valid player name.", row.player));
+        }
+        if row.position < 1 {
+            return Err(format!("{} has a position below 1.", row.player));
+        }
+        if row.score < 0 {
+            return Err(format!("{} has a negative score.", row.player));
+        }
+    }
+
+    rows.sort_by_key(|row| row.position);
+    for pair in rows.windows(2) {
+        let in_order = if descending {
+            pair[0].score >= pair[1].score
+        } else {
+            pair[0].score <= pair[1].score
+        };
+        if !in_order {
+            return Err(format!(
+                "{} [{}] and {} [{}] have their scores in the wrong order for this game.",
+                pair[0].player, pair[0].position, pair[1].player, pair[1].position
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Vec<SubmissionRow>, String> {
+        parse_submission("board.csv", content.as_bytes(), true)
+    }
+
+    #[test]
+    fn skips_the_header() {
+        let rows = parse(" Player, Position, Score \nFesa,1,50\njeb,2,40\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].player, "Fesa");
+    }
+
+    #[test]
+    fn keeps_a_first_row_named_like_the_header() {
+        let rows = parse("Player123,1,500\nFesa,2,400").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].player, "Player123");
+    }
+
+    #[test]
+    fn rejects_duplicate_positions() {
+        assert_eq!(
+            parse("Fesa,1,50\njeb,1,40").err().unwrap(),
+            "Every position may only appear once."
+        );
+    }
+
+    #[test]
+    fn rejects_non_numeric_scores() {
+        assert_eq!(
+            parse("Fesa,1,lots").err().unwrap(),
+            "Line 1 has an invalid score: `lots`"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_files() {
+        assert_eq!(
+            parse("player,position,score\n\n").err().unwrap(),
+            "The attached file doesn't contain any rows."
+        );
+        assert_eq!(
+            parse_submission("board.json", b"[]", true).err().unwrap(),
+            "The attached file doesn't contain any rows."
+        );
+    }
+
+    #[test]
+    fn rejects_more_rows_than_the_limit() {
+        let at_limit: String = (1..=MAX_ROWS)
+            .map(|position| format!("player{},{},{}\n", position, position, MAX_ROWS - position))
+            .collect();
+        assert_eq!(parse(&at_limit).unwrap().len(), MAX_ROWS);
+
+        let over_limit = format!("{}extra,{},0\n", at_limit, MAX_ROWS + 1);
+        assert_eq!(
+            parse(&over_limit).err().unwrap(),
+            format!(
+                "A submission can't have more than {} rows, this one has {}.",
+                MAX_ROWS,
+                MAX_ROWS + 1
+            )
+        );
+    }
+}
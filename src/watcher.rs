@@ -0,0 +1,111 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serenity::{builder::CreateEmbed, http::Http, model::prelude::ChannelId, utils::Colour};
+use tokio::time::sleep;
+
+use crate::{
+    commands::players_page,
+    follows,
+    games::{Game, GameRegistry},
+    store::LeaderboardStore,
+};
+
+/// How often the submissions table is checked for new snapshots
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How many players an announcement shows
+const ANNOUNCED_PLAYERS: usize = 10;
+
+/// Polls for valid submissions that weren't announced yet, no matter how they got into the
+/// database, and announces them in the configured channels and to the followers of players that
+/// moved
+pub async fn watch(
+    http: Arc<Http>,
+    store: Arc<dyn LeaderboardStore>,
+    games: Arc<Mutex<GameRegistry>>,
+) {
+    loop {
+        sleep(POLL_INTERVAL).await;
+
+        let submissions = match store.unannounced_submissions().await {
+            Ok(submissions) => submissions,
+            Err(why) => {
+                println!("Cannot check for new submissions: {}", why);
+                continue;
+            }
+        };
+
+        for submission in submissions {
+            let game = games
+                .lock()
+                .ok()
+                .and_then(|games| games.by_name(&submission.game).cloned());
+            // Submissions of games that aren't registered are skipped for good
+            if let Some(game) = game {
+                announce(&http, store.as_ref(), &game, submission.unix_time_stamp).await;
+                follows::notify_followers(
+                    http.clone(),
+                    store.clone(),
+                    game,
+                    submission.unix_time_stamp,
+                )
+                .await;
+            }
+
+            if let Err(why) = store
+                .mark_submission_announced(&submission.game, submission.unix_time_stamp)
+                .await
+            {
+                println!("Cannot mark submission as announced: {}", why);
+            }
+        }
+    }
+}
+
+async fn announce(http: &Arc<Http>, store: &dyn LeaderboardStore, game: &Game, submission_id: i64) {
+    let channels = match store.announcement_channels(&game.name).await {
+        Ok(channels) if channels.is_empty() => return,
+        Ok(channels) => channels,
+        Err(why) => {
+            println!("Cannot load announcement channels: {}", why);
+            return;
+        }
+    };
+    let players = match store
+        .submission_leaderboard(&game.name, submission_id)
+        .await
+    {
+        Ok(players) if players.is_empty() => return,
+        Ok(players) => players,
+        Err(why) => {
+            println!("Cannot load the announced leaderboard: {}", why);
+            return;
+        }
+    };
+
+    let top = &players[..players.len().min(ANNOUNCED_PLAYERS)];
+    // Positions can have gaps, so the range comes from the rows themselves
+    let (first, last) = (top[0].position, top[top.len() - 1].position);
+    let mut embed = CreateEmbed::default();
+    embed.footer(|f| f.text(format!("Submission ID: {}", submission_id)));
+    embed.timestamp(Utc::now().to_rfc3339());
+    embed.colour(Colour::from_rgb(106, 86, 246));
+    embed.description(format!(
+        "**New {} leaderboard!**\n{}",
+        game.name,
+        players_page(top, game, first as i64, last as i64, None)
+    ));
+
+    for channel_id in channels {
+        if let Err(why) = ChannelId(channel_id as u64)
+            .send_message(http, |m| m.set_embed(embed.clone()))
+            .await
+        {
+            println!("Cannot announce in {}: {}", channel_id, why);
+        }
+    }
+}